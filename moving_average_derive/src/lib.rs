@@ -0,0 +1,186 @@
+//! `#[derive(MovingStats)]` for the `moving-average` crate; enable its
+//! `derive` feature and use the re-export rather than this crate directly.
+
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
+/// Generates `<Name>Stats`, holding one `Moving` per field of a struct
+/// with named fields and an `add(&Name)` that feeds every field in.
+///
+/// Fields marked `#[moving(skip)]` are left out; every other field must
+/// be `Copy` and implement `MovingInput`.
+#[proc_macro_derive(MovingStats, attributes(moving))]
+pub fn derive_moving_stats(input: TokenStream) -> TokenStream {
+    let code = match expand(input) {
+        Ok(code) => code,
+        Err(message) => format!("compile_error!({message:?});"),
+    };
+    code.parse().expect("generated code is valid Rust")
+}
+
+struct Field {
+    name: String,
+    ty: String,
+}
+
+fn expand(input: TokenStream) -> Result<String, String> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let mut position = 0;
+    let mut visibility = String::new();
+    let name = loop {
+        match tokens.get(position) {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '#' => position += 2,
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "pub" => {
+                visibility = skip_visibility(&tokens, &mut position);
+            }
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "struct" => {
+                match tokens.get(position + 1) {
+                    Some(TokenTree::Ident(name)) => break name.to_string(),
+                    _ => return Err("expected a struct name".into()),
+                }
+            }
+            Some(TokenTree::Ident(ident))
+                if ["enum", "union"].contains(&ident.to_string().as_str()) =>
+            {
+                return Err("MovingStats can only be derived for structs".into());
+            }
+            Some(_) => position += 1,
+            None => return Err("expected a struct".into()),
+        }
+    };
+    let fields = match tokens.get(position + 2) {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            parse_fields(group.stream())?
+        }
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+            return Err("MovingStats does not support generic structs".into());
+        }
+        _ => return Err("MovingStats needs a struct with named fields".into()),
+    };
+
+    let declarations: String = fields
+        .iter()
+        .map(|field| {
+            format!(
+                "pub {}: ::moving_average::Moving<{}>,",
+                field.name, field.ty
+            )
+        })
+        .collect();
+    let constructors: String = fields
+        .iter()
+        .map(|field| format!("{}: ::moving_average::Moving::new(),", field.name))
+        .collect();
+    let adds: String = fields
+        .iter()
+        .map(|field| format!("self.{0}.add(sample.{0});", field.name))
+        .collect();
+    let resets: String = fields
+        .iter()
+        .map(|field| format!("self.{}.reset();", field.name))
+        .collect();
+    let count = fields
+        .first()
+        .map_or("0".into(), |field| format!("self.{}.count()", field.name));
+    Ok(format!(
+        "/// One `Moving` per field of [`{name}`], generated by `MovingStats`.
+        #[derive(Debug, Clone)]
+        {visibility} struct {name}Stats {{ {declarations} }}
+
+        impl {name}Stats {{
+            {visibility} fn new() -> Self {{
+                Self {{ {constructors} }}
+            }}
+
+            /// Adds every field of `sample` to its average.
+            {visibility} fn add(&mut self, sample: &{name}) {{
+                {adds}
+            }}
+
+            /// Number of samples added.
+            {visibility} fn count(&self) -> usize {{
+                {count}
+            }}
+
+            {visibility} fn reset(&mut self) {{
+                {resets}
+            }}
+        }}
+
+        impl ::core::default::Default for {name}Stats {{
+            fn default() -> Self {{
+                Self::new()
+            }}
+        }}"
+    ))
+}
+
+/// Consumes `pub` and an optional `(crate)`-style restriction.
+fn skip_visibility(tokens: &[TokenTree], position: &mut usize) -> String {
+    *position += 1;
+    match tokens.get(*position) {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            *position += 1;
+            format!("pub{group}")
+        }
+        _ => "pub".into(),
+    }
+}
+
+fn parse_fields(stream: TokenStream) -> Result<Vec<Field>, String> {
+    let tokens: Vec<TokenTree> = stream.into_iter().collect();
+    let mut fields = Vec::new();
+    let mut position = 0;
+    while position < tokens.len() {
+        let mut skip = false;
+        while let Some(TokenTree::Punct(punct)) = tokens.get(position) {
+            if punct.as_char() != '#' {
+                break;
+            }
+            if let Some(TokenTree::Group(attribute)) = tokens.get(position + 1) {
+                skip |= is_skip(attribute.stream());
+            }
+            position += 2;
+        }
+        if matches!(&tokens.get(position), Some(TokenTree::Ident(ident)) if ident.to_string() == "pub")
+        {
+            skip_visibility(&tokens, &mut position);
+        }
+        let name = match tokens.get(position) {
+            Some(TokenTree::Ident(name)) => name.to_string(),
+            _ => return Err("expected a field name".into()),
+        };
+        position += 2;
+        let mut ty = Vec::new();
+        let mut depth = 0;
+        while let Some(token) = tokens.get(position) {
+            position += 1;
+            if let TokenTree::Punct(punct) = token {
+                match punct.as_char() {
+                    '<' => depth += 1,
+                    '>' => depth -= 1,
+                    ',' if depth == 0 => break,
+                    _ => {}
+                }
+            }
+            ty.push(token.clone());
+        }
+        if !skip {
+            let ty = ty.into_iter().collect::<TokenStream>().to_string();
+            fields.push(Field { name, ty });
+        }
+    }
+    Ok(fields)
+}
+
+/// Whether an attribute's contents are `moving(skip)`.
+fn is_skip(attribute: TokenStream) -> bool {
+    let tokens: Vec<TokenTree> = attribute.into_iter().collect();
+    match tokens.as_slice() {
+        [TokenTree::Ident(ident), TokenTree::Group(arguments)] if ident.to_string() == "moving" => {
+            arguments.stream().into_iter().any(
+                |token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "skip"),
+            )
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::mpsc::{self, Sender};
+use std::thread::JoinHandle;
+
+use crate::{Moving, MovingInput, MovingSnapshot, SharedMoving};
+
+/// Background thread folding values received over a channel into a
+/// [`Moving`], from [`spawn_aggregator`].
+///
+/// The thread runs until every sender is dropped; [`join`](Self::join)
+/// then returns the final accumulator.
+#[derive(Debug)]
+pub struct Aggregator<T> {
+    moving: SharedMoving<T>,
+    thread: JoinHandle<()>,
+}
+
+/// Spawns a thread that adds every value sent on the returned sender to
+/// `moving`, so producers never wait on the accumulator's lock.
+///
+/// ```rust
+/// use moving_average::{spawn_aggregator, Moving};
+///
+/// let (sender, aggregator) = spawn_aggregator(Moving::<u32>::new());
+/// for worker in 0..4 {
+///     let sender = sender.clone();
+///     std::thread::spawn(move || sender.send(10 * worker).unwrap());
+/// }
+/// drop(sender);
+/// let moving = aggregator.join();
+/// assert_eq!(moving.count(), 4);
+/// assert!((moving.mean() - 15.0).abs() < 1e-12);
+/// ```
+pub fn spawn_aggregator<T>(moving: Moving<T>) -> (Sender<T>, Aggregator<T>)
+where
+    T: MovingInput + Send + Sync + 'static,
+{
+    let (sender, values) = mpsc::channel::<T>();
+    let moving = SharedMoving::new(moving);
+    let thread = {
+        let moving = moving.clone();
+        std::thread::spawn(move || {
+            for value in values {
+                moving.add(value);
+            }
+        })
+    };
+    (sender, Aggregator { moving, thread })
+}
+
+impl<T> Aggregator<T>
+where
+    T: MovingInput,
+{
+    /// The statistics over the values received so far; values still in
+    /// the channel are not included yet.
+    pub fn snapshot(&self) -> MovingSnapshot {
+        self.moving.snapshot()
+    }
+
+    /// Handle to the accumulator, e.g. for
+    /// [`report_every`](SharedMoving::report_every).
+    pub fn moving(&self) -> &SharedMoving<T> {
+        &self.moving
+    }
+
+    /// Whether every sender has been dropped and every value folded in.
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Waits for every sender to be dropped and the channel to drain, then
+    /// returns the accumulator.
+    pub fn join(self) -> Moving<T> {
+        let _ = self.thread.join();
+        self.moving.read(Moving::clone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_while_running() {
+        let (sender, aggregator) = spawn_aggregator(Moving::<f64>::new().with_mode_tracking());
+        for value in [1.0, 2.0, 2.0] {
+            sender.send(value).unwrap();
+        }
+        while aggregator.snapshot().count < 3 {
+            std::thread::yield_now();
+        }
+        assert!(!aggregator.is_finished());
+        assert_eq!(aggregator.snapshot().mode, Some(2.0));
+        drop(sender);
+        assert_eq!(aggregator.join().mean(), 5.0 / 3.0);
+    }
+}
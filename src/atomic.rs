@@ -0,0 +1,104 @@
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use crate::{Moving, MovingInput};
+
+/// Mean that many threads can add to through a shared reference, without
+/// locking.
+///
+/// The sum is kept as `f64` bits updated with a compare-and-swap loop and
+/// the count with an atomic increment. A mean read while adds are in
+/// flight may already include a value whose count has not landed yet; once
+/// writers are done it is exact.
+///
+/// ```rust
+/// use moving_average::AtomicMoving;
+///
+/// let latency: AtomicMoving<u32> = AtomicMoving::new();
+/// std::thread::scope(|scope| {
+///     for worker in 0..4 {
+///         let latency = &latency;
+///         scope.spawn(move || latency.add(10 * worker));
+///     }
+/// });
+/// assert_eq!(latency.count(), 4);
+/// assert_eq!(latency.mean(), 15.0);
+/// ```
+#[derive(Debug, Default)]
+pub struct AtomicMoving<T> {
+    count: AtomicUsize,
+    sum: AtomicU64,
+    phantom: core::marker::PhantomData<fn(T)>,
+}
+
+impl<T> AtomicMoving<T>
+where
+    T: MovingInput,
+{
+    pub fn new() -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+            sum: AtomicU64::new(0.0_f64.to_bits()),
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    pub fn add(&self, value: T) {
+        let value = T::to_f64(value);
+        let _ = self
+            .sum
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |sum| {
+                Some((f64::from_bits(sum) + value).to_bits())
+            });
+        self.count.fetch_add(1, Ordering::Release);
+    }
+
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Acquire)
+    }
+
+    pub fn sum(&self) -> f64 {
+        f64::from_bits(self.sum.load(Ordering::Acquire))
+    }
+
+    pub fn mean(&self) -> f64 {
+        let count = self.count();
+        if count == 0 {
+            return 0.0;
+        }
+        self.sum() / count as f64
+    }
+
+    /// Copies the current count and mean into a single-threaded [`Moving`].
+    pub fn to_moving(&self) -> Moving<T> {
+        Moving::with_initial(self.mean(), self.count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_adds() {
+        let moving: AtomicMoving<u64> = AtomicMoving::new();
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for value in 1..=1000 {
+                        moving.add(value);
+                    }
+                });
+            }
+        });
+        assert_eq!(moving.count(), 8000);
+        assert_eq!(moving.sum(), 8.0 * 500_500.0);
+        assert_eq!(moving.mean(), 500.5);
+        assert_eq!(moving.to_moving().count(), 8000);
+    }
+
+    #[test]
+    fn empty_mean_is_zero() {
+        let moving: AtomicMoving<f32> = AtomicMoving::new();
+        assert_eq!(moving.mean(), 0.0);
+    }
+}
@@ -0,0 +1,288 @@
+//! Streaming statistics over numbers read from stdin or a file.
+//!
+//! Built with the `cli` feature: `cargo install moving-average --features cli`.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::ExitCode;
+
+use moving_average::{Moving, MovingError, WindowedMoving};
+
+const USAGE: &str = "\
+Usage: moving-average [OPTIONS] [FILE]
+
+Reads one number per line (or one column of delimited lines) from FILE,
+or stdin when no FILE is given, and prints running statistics.
+Lines that do not parse as a number, such as CSV headers, are skipped.
+
+Options:
+  -c, --column <N>        Read the Nth column, counting from 1
+  -d, --delimiter <CHAR>  Column delimiter [default: ,]
+  -w, --window <N>        Also report the mean of the last N values
+  -p, --percentile <P>    Also report the estimated Pth percentile, e.g. 99;
+                          may be repeated
+  -t, --threshold <N>     Report each time the mean reaches N, and exit with
+                          status 1 if it ever did
+  -f, --follow            Print statistics after every value, not just at the end
+  -h, --help              Print this help";
+
+struct Options {
+    column: Option<usize>,
+    delimiter: char,
+    window: Option<usize>,
+    percentiles: Vec<f64>,
+    threshold: Option<f64>,
+    follow: bool,
+    path: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        column: None,
+        delimiter: ',',
+        window: None,
+        percentiles: Vec::new(),
+        threshold: None,
+        follow: false,
+        path: None,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-f" | "--follow" => options.follow = true,
+            "-c" | "--column" => {
+                let column = args
+                    .next()
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .filter(|column| *column > 0)
+                    .ok_or("--column expects a number from 1")?;
+                options.column = Some(column);
+            }
+            "-w" | "--window" => {
+                let window = args
+                    .next()
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .filter(|window| *window > 0)
+                    .ok_or("--window expects a number from 1")?;
+                options.window = Some(window);
+            }
+            "-p" | "--percentile" => {
+                let percentile = args
+                    .next()
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .filter(|percentile| (0.0..=100.0).contains(percentile))
+                    .ok_or("--percentile expects a number from 0 to 100")?;
+                options.percentiles.push(percentile);
+            }
+            "-t" | "--threshold" => {
+                let threshold = args
+                    .next()
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .filter(|threshold| threshold.is_finite())
+                    .ok_or("--threshold expects a number")?;
+                options.threshold = Some(threshold);
+            }
+            "-d" | "--delimiter" => {
+                let delimiter = args.next().ok_or("--delimiter expects a character")?;
+                let mut chars = delimiter.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(delimiter), None) => options.delimiter = delimiter,
+                    _ => return Err("--delimiter expects a single character".into()),
+                }
+            }
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown option {flag}"));
+            }
+            path => {
+                if options.path.replace(path.to_string()).is_some() {
+                    return Err("only one FILE can be read".into());
+                }
+            }
+        }
+    }
+    Ok(Some(options))
+}
+
+fn field<'a>(line: &'a str, options: &Options) -> Option<&'a str> {
+    match options.column {
+        Some(column) => line.split(options.delimiter).nth(column - 1),
+        None => Some(line),
+    }
+}
+
+fn summary(
+    moving: &Moving<f64>,
+    windowed: Option<&WindowedMoving<f64>>,
+    options: &Options,
+    skipped: usize,
+) -> String {
+    let optional = |value: Option<f64>| value.map_or("-".to_string(), |value| value.to_string());
+    let mut summary = format!(
+        "count={} mean={} min={} max={} mode={} skipped={}",
+        moving.count(),
+        moving.mean(),
+        optional(moving.min()),
+        optional(moving.max()),
+        optional(moving.mode()),
+        skipped
+    );
+    for percentile in &options.percentiles {
+        let estimate = moving.quantile(percentile / 100.0);
+        summary.push_str(&format!(" p{percentile}={}", optional(estimate)));
+    }
+    if let Some(windowed) = windowed {
+        summary.push_str(&format!(" window_mean={}", windowed.mean()));
+    }
+    summary
+}
+
+/// Returns whether the threshold was ever reached.
+fn run(options: Options) -> io::Result<bool> {
+    let input: Box<dyn BufRead> = match &options.path {
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+        None => Box::new(io::stdin().lock()),
+    };
+    let mut output = io::stdout().lock();
+    let mut moving: Moving<f64> = options
+        .percentiles
+        .iter()
+        .fold(Moving::new().with_mode_tracking(), |moving, percentile| {
+            moving.with_quantile(percentile / 100.0)
+        });
+    if let Some(threshold) = options.threshold {
+        moving = moving.with_threshold(threshold);
+    }
+    let mut windowed: Option<WindowedMoving<f64>> = options.window.map(WindowedMoving::new);
+    let mut skipped = 0;
+    let mut reached = false;
+    let mut ever_reached = false;
+
+    for line in input.lines() {
+        let line = line?;
+        match field(&line, &options).and_then(|field| field.trim().parse::<f64>().ok()) {
+            Some(value) => {
+                let breach = match moving.add_with_result(value) {
+                    Err(error @ MovingError::ThresholdReached(_)) => Some(error),
+                    _ => None,
+                };
+                if let Some(error) = &breach {
+                    if !reached {
+                        eprintln!("moving-average: {error}");
+                    }
+                    ever_reached = true;
+                }
+                reached = breach.is_some();
+                if let Some(windowed) = &mut windowed {
+                    windowed.add(value);
+                }
+            }
+            None => {
+                skipped += 1;
+                continue;
+            }
+        }
+        if options.follow {
+            writeln!(
+                output,
+                "{}",
+                summary(&moving, windowed.as_ref(), &options, skipped)
+            )?;
+        }
+    }
+    if !options.follow {
+        writeln!(
+            output,
+            "{}",
+            summary(&moving, windowed.as_ref(), &options, skipped)
+        )?;
+    }
+    Ok(ever_reached)
+}
+
+fn main() -> ExitCode {
+    match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => match run(options) {
+            Ok(false) => ExitCode::SUCCESS,
+            Ok(true) => ExitCode::from(1),
+            Err(error) => {
+                eprintln!("moving-average: {error}");
+                ExitCode::FAILURE
+            }
+        },
+        Ok(None) => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("moving-average: {error}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Options>, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_every_option() {
+        let options = parse(&[
+            "-c",
+            "2",
+            "-d",
+            ";",
+            "-w",
+            "5",
+            "-p",
+            "99",
+            "--percentile",
+            "50",
+            "-t",
+            "12.5",
+            "-f",
+            "data.csv",
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(options.column, Some(2));
+        assert_eq!(options.delimiter, ';');
+        assert_eq!(options.window, Some(5));
+        assert_eq!(options.percentiles, [99.0, 50.0]);
+        assert_eq!(options.threshold, Some(12.5));
+        assert!(options.follow);
+        assert_eq!(options.path.as_deref(), Some("data.csv"));
+    }
+
+    #[test]
+    fn defaults_and_help() {
+        let options = parse(&[]).unwrap().unwrap();
+        assert_eq!(options.column, None);
+        assert_eq!(options.delimiter, ',');
+        assert_eq!(options.threshold, None);
+        assert!(!options.follow);
+        assert!(parse(&["-w", "3", "--help"]).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(parse(&["--column", "0"]).is_err());
+        assert!(parse(&["--window"]).is_err());
+        assert!(parse(&["-p", "101"]).is_err());
+        assert!(parse(&["--threshold", "high"]).is_err());
+        assert!(parse(&["-t", "NaN"]).is_err());
+        assert!(parse(&["-d", "ab"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+        assert!(parse(&["a.csv", "b.csv"]).is_err());
+    }
+
+    #[test]
+    fn reads_the_configured_column() {
+        let options = parse(&["-c", "2", "-d", "|"]).unwrap().unwrap();
+        assert_eq!(field("a|4.5|c", &options), Some("4.5"));
+        assert_eq!(field("a", &options), None);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::{
+    Ema, MissingPolicy, ModeBackend, Moving, MovingEvent, MovingInput, NonFinitePolicy,
+    OutlierRule, OverflowPolicy, Statistic, Threshold, WindowedMoving,
+};
+
+/// Step-by-step configuration of a [`Moving`], ending in
+/// [`build`](Self::build).
+///
+/// Each option maps onto the `with_*` method of the same name on
+/// [`Moving`]; ingestion stages run in the order they are added.
+///
+/// ```rust
+/// use moving_average::{MissingPolicy, Moving};
+///
+/// let mut moving_average: Moving<f64> = Moving::builder()
+///     .mode_tracking(true)
+///     .missing_policy(MissingPolicy::Zero)
+///     .valid_range(0.0, 100.0)
+///     .build();
+/// moving_average.add(40.0);
+/// moving_average.add_opt(None).unwrap();
+/// moving_average.add(400.0);
+/// assert_eq!(moving_average, 20.0);
+/// ```
+#[derive(Debug)]
+pub struct MovingBuilder<T> {
+    moving: Moving<T>,
+    mode_tracking: bool,
+    median: bool,
+}
+
+impl<T> Moving<T>
+where
+    T: MovingInput,
+{
+    pub fn builder() -> MovingBuilder<T> {
+        MovingBuilder::new()
+    }
+}
+
+impl<T> Default for MovingBuilder<T>
+where
+    T: MovingInput,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> MovingBuilder<T>
+where
+    T: MovingInput,
+{
+    pub fn new() -> Self {
+        Self {
+            moving: Moving::new(),
+            mode_tracking: false,
+            median: false,
+        }
+    }
+
+    /// Starts from a previously computed mean over `count` values.
+    pub fn initial(mut self, mean: f64, count: usize) -> Self {
+        self.moving.count = count;
+        self.moving.weight = count as f64;
+        self.moving.mean = if count == 0 { 0.0 } else { mean };
+        if self.moving.compensated.is_some() {
+            self.moving = self.moving.with_compensated_mean();
+        }
+        self
+    }
+
+    pub fn threshold(mut self, threshold: impl Into<Threshold>) -> Self {
+        self.moving = self.moving.with_threshold(threshold);
+        self
+    }
+
+    pub fn threshold_on(mut self, statistic: Statistic, threshold: impl Into<Threshold>) -> Self {
+        self.moving = self.moving.with_threshold_on(statistic, threshold);
+        self
+    }
+
+    pub fn compensated_mean(mut self) -> Self {
+        self.moving = self.moving.with_compensated_mean();
+        self
+    }
+
+    pub fn history(mut self, depth: usize) -> Self {
+        self.moving = self.moving.with_history(depth);
+        self
+    }
+
+    pub fn crossing_reference(mut self, reference: f64) -> Self {
+        self.moving = self.moving.with_crossing_reference(reference);
+        self
+    }
+
+    pub fn missing_policy(mut self, policy: MissingPolicy) -> Self {
+        self.moving = self.moving.with_missing_policy(policy);
+        self
+    }
+
+    #[cfg(feature = "std")]
+    pub fn gap_policy(mut self, interval: core::time::Duration, policy: crate::GapPolicy) -> Self {
+        self.moving = self.moving.with_gap_policy(interval, policy);
+        self
+    }
+
+    #[cfg(feature = "std")]
+    pub fn max_gap(mut self, samples: u32) -> Self {
+        self.moving = self.moving.with_max_gap(samples);
+        self
+    }
+
+    pub fn non_finite_policy(mut self, policy: NonFinitePolicy) -> Self {
+        self.moving = self.moving.with_non_finite_policy(policy);
+        self
+    }
+
+    pub fn overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.moving = self.moving.with_overflow_policy(policy);
+        self
+    }
+
+    pub fn mode_tracking(mut self, enabled: bool) -> Self {
+        self.mode_tracking = enabled;
+        self
+    }
+
+    /// Counts value frequencies with `backend`, enabling mode tracking.
+    pub fn mode_backend(mut self, backend: ModeBackend) -> Self {
+        self.moving = self.moving.with_mode_backend(backend);
+        self.mode_tracking = true;
+        self
+    }
+
+    /// Caps the memory used for mode tracking, enabling it.
+    pub fn memory_budget(mut self, bytes: usize) -> Self {
+        self.moving = self.moving.with_memory_budget(bytes);
+        self.mode_tracking = true;
+        self
+    }
+
+    pub fn median(mut self, enabled: bool) -> Self {
+        self.median = enabled;
+        self
+    }
+
+    pub fn quantile(mut self, q: f64) -> Self {
+        self.moving = self.moving.with_quantile(q);
+        self
+    }
+
+    pub fn valid_range(mut self, min: f64, max: f64) -> Self {
+        self.moving = self.moving.with_valid_range(min, max);
+        self
+    }
+
+    pub fn outlier_rejection(mut self, rule: OutlierRule) -> Self {
+        self.moving = self.moving.with_outlier_rejection(rule);
+        self
+    }
+
+    pub fn filter(mut self, filter: impl Fn(f64) -> bool + Send + Sync + 'static) -> Self {
+        self.moving = self.moving.with_filter(filter);
+        self
+    }
+
+    pub fn transform(mut self, transform: impl Fn(f64) -> f64 + Send + Sync + 'static) -> Self {
+        self.moving = self.moving.with_transform(transform);
+        self
+    }
+
+    pub fn observer(mut self, observer: impl Fn(&MovingEvent) + Send + Sync + 'static) -> Self {
+        self.moving = self.moving.with_observer(observer);
+        self
+    }
+
+    pub fn build(self) -> Moving<T> {
+        let mut moving = self.moving;
+        if self.mode_tracking {
+            moving = moving.with_mode_tracking();
+        } else {
+            moving.mode = None;
+        }
+        if self.median {
+            moving = moving.with_median();
+        }
+        moving
+    }
+
+    /// Ends in a [`WindowedMoving`] over the last
+    /// `window` samples instead of a cumulative [`Moving`]; the other
+    /// options configure `Moving` alone and are not carried over.
+    pub fn build_windowed(self, window: usize) -> WindowedMoving<T> {
+        WindowedMoving::new(window)
+    }
+
+    /// Ends in an [`Ema`] whose samples decay by `alpha` instead of a
+    /// cumulative [`Moving`]; the other options configure `Moving` alone and
+    /// are not carried over.
+    pub fn build_ema(self, alpha: f64) -> Ema<T> {
+        Ema::new(alpha)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_reach_moving() {
+        let mut moving: Moving<u32> = Moving::builder()
+            .initial(10.0, 2)
+            .history(2)
+            .median(true)
+            .quantile(0.5)
+            .filter(|value| value != 13.0)
+            .transform(|value| value * 2.0)
+            .build();
+        moving.add(13);
+        moving.add(5);
+        assert_eq!(moving.count(), 3);
+        assert_eq!(moving.mean(), 10.0);
+        assert_eq!(moving.median(), Some(10.0));
+        assert_eq!(moving.quantile(0.5), Some(10.0));
+        assert_eq!(moving.rejected(), 1);
+        assert_eq!(moving.mode(), None);
+    }
+
+    #[test]
+    fn mode_tracking_toggles() {
+        let moving: Moving<u8> = MovingBuilder::new()
+            .memory_budget(1024)
+            .mode_tracking(false)
+            .build();
+        assert_eq!(moving.mode_backend(), None);
+        let moving: Moving<u8> = MovingBuilder::new().mode_tracking(true).build();
+        assert!(moving.mode_backend().is_some());
+    }
+
+    #[test]
+    fn window_and_decay() {
+        let mut windowed = Moving::<u32>::builder().build_windowed(2);
+        let mut ema = Moving::<u32>::builder().build_ema(0.5);
+        for value in [10, 20, 40] {
+            windowed.add(value);
+            ema.add(value);
+        }
+        assert_eq!(windowed.window(), 2);
+        assert_eq!(windowed.mean(), 30.0);
+        assert_eq!(ema.alpha(), 0.5);
+        assert_eq!(ema.mean(), 27.5);
+    }
+}
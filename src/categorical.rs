@@ -0,0 +1,229 @@
+use core::hash::Hash;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+struct Frequency {
+    count: usize,
+    weight: f64,
+    first_seen: usize,
+}
+
+impl Frequency {
+    /// Ordering used for the mode and top-k: heavier first, then more
+    /// frequent, then seen earlier.
+    fn rank(&self, other: &Frequency) -> core::cmp::Ordering {
+        other
+            .weight
+            .total_cmp(&self.weight)
+            .then(other.count.cmp(&self.count))
+            .then(self.first_seen.cmp(&other.first_seen))
+    }
+}
+
+/// Frequencies and mode over non-numeric keys such as strings or enums.
+///
+/// Samples added with [`add`](Self::add) weigh one each; with
+/// [`add_weighted`](Self::add_weighted) the mode is the key with the largest
+/// accumulated weight. Ties for the mode, and for positions in
+/// [`top_k`](Self::top_k), go to the key with more samples and then to the
+/// key that was seen first.
+///
+/// ```rust
+/// use moving_average::CategoricalMoving;
+///
+/// let mut statuses = CategoricalMoving::new();
+/// for status in ["ok", "timeout", "ok", "error", "timeout", "ok"] {
+///     statuses.add(status);
+/// }
+/// assert_eq!(statuses.mode(), Some(&"ok"));
+/// assert_eq!(statuses.top_k(2), vec![(&"ok", 3), (&"timeout", 2)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CategoricalMoving<K> {
+    frequencies: HashMap<K, Frequency>,
+    count: usize,
+    total_weight: f64,
+    mode: Option<K>,
+}
+
+impl<K> Default for CategoricalMoving<K> {
+    fn default() -> Self {
+        Self {
+            frequencies: HashMap::new(),
+            count: 0,
+            total_weight: 0.0,
+            mode: None,
+        }
+    }
+}
+
+impl<K> CategoricalMoving<K>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, key: K) {
+        self.add_weighted(key, 1.0);
+    }
+
+    /// Adds a sample carrying `weight`; weights that are not positive and
+    /// finite are ignored.
+    pub fn add_weighted(&mut self, key: K, weight: f64) {
+        if !(weight.is_finite() && weight > 0.0) {
+            return;
+        }
+        let first_seen = self.frequencies.len();
+        let frequency = self.frequencies.entry(key.clone()).or_insert(Frequency {
+            count: 0,
+            weight: 0.0,
+            first_seen,
+        });
+        frequency.count += 1;
+        frequency.weight += weight;
+        self.count += 1;
+        self.total_weight += weight;
+
+        let frequency = &self.frequencies[&key];
+        let replaces_mode = self.mode.as_ref().is_none_or(|mode| {
+            frequency.rank(&self.frequencies[mode]) == core::cmp::Ordering::Less
+        });
+        if replaces_mode {
+            self.mode = Some(key);
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Number of distinct keys seen.
+    pub fn distinct(&self) -> usize {
+        self.frequencies.len()
+    }
+
+    pub fn frequency(&self, key: &K) -> usize {
+        self.frequencies
+            .get(key)
+            .map_or(0, |frequency| frequency.count)
+    }
+
+    /// Accumulated weight of `key`.
+    pub fn weight(&self, key: &K) -> f64 {
+        self.frequencies
+            .get(key)
+            .map_or(0.0, |frequency| frequency.weight)
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.total_weight
+    }
+
+    /// Share of the total weight carried by `key`.
+    pub fn proportion(&self, key: &K) -> Option<f64> {
+        (self.count > 0).then(|| self.weight(key) / self.total_weight)
+    }
+
+    pub fn mode(&self) -> Option<&K> {
+        self.mode.as_ref()
+    }
+
+    /// Every key with its count, in no particular order.
+    pub fn frequencies(&self) -> impl Iterator<Item = (&K, usize)> {
+        self.frequencies
+            .iter()
+            .map(|(key, frequency)| (key, frequency.count))
+    }
+
+    /// The `k` highest ranked keys with their counts, mode first.
+    pub fn top_k(&self, k: usize) -> Vec<(&K, usize)> {
+        let mut entries: Vec<(&K, &Frequency)> = self.frequencies.iter().collect();
+        entries.sort_by(|(_, a), (_, b)| a.rank(b));
+        entries
+            .into_iter()
+            .take(k)
+            .map(|(key, frequency)| (key, frequency.count))
+            .collect()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum Color {
+        Red,
+        Green,
+        Blue,
+    }
+
+    #[test]
+    fn ties_go_to_first_seen() {
+        let mut colors = CategoricalMoving::new();
+        colors.add(Color::Green);
+        colors.add(Color::Red);
+        colors.add(Color::Red);
+        colors.add(Color::Green);
+        assert_eq!(colors.mode(), Some(&Color::Green));
+        colors.add(Color::Red);
+        assert_eq!(colors.mode(), Some(&Color::Red));
+        assert_eq!(colors.frequency(&Color::Blue), 0);
+        assert_eq!(colors.proportion(&Color::Red), Some(0.6));
+    }
+
+    #[test]
+    fn top_k_orders_by_count() {
+        let mut words: CategoricalMoving<String> = CategoricalMoving::new();
+        for word in "b a c a c d".split(' ') {
+            words.add(word.to_string());
+        }
+        let top: Vec<(&str, usize)> = words
+            .top_k(3)
+            .into_iter()
+            .map(|(word, count)| (word.as_str(), count))
+            .collect();
+        assert_eq!(top, vec![("a", 2), ("c", 2), ("b", 1)]);
+        assert_eq!(words.distinct(), 4);
+        assert_eq!(
+            words.frequencies().map(|(_, count)| count).sum::<usize>(),
+            6
+        );
+    }
+
+    #[test]
+    fn weighted_mode() {
+        let mut sizes = CategoricalMoving::new();
+        sizes.add_weighted("small", 1.0);
+        sizes.add_weighted("small", 1.0);
+        sizes.add_weighted("small", 1.0);
+        sizes.add_weighted("large", 10.0);
+        assert_eq!(sizes.mode(), Some(&"large"));
+        assert_eq!(sizes.frequency(&"small"), 3);
+        assert_eq!(sizes.weight(&"large"), 10.0);
+        assert_eq!(sizes.top_k(2), vec![(&"large", 1), (&"small", 3)]);
+        assert_eq!(sizes.proportion(&"small"), Some(3.0 / 13.0));
+
+        sizes.add_weighted("medium", 3.0);
+        sizes.add_weighted("medium", 0.0);
+        sizes.add_weighted("medium", f64::NAN);
+        assert_eq!(sizes.count(), 5);
+        assert_eq!(sizes.top_k(3)[1], (&"small", 3));
+    }
+
+    #[test]
+    fn reset_clears() {
+        let mut colors = CategoricalMoving::new();
+        colors.add(Color::Blue);
+        colors.reset();
+        assert_eq!(colors.mode(), None);
+        assert_eq!(colors.count(), 0);
+        assert_eq!(colors.proportion(&Color::Blue), None);
+    }
+}
@@ -0,0 +1,96 @@
+use core::cmp::Ordering;
+
+use crate::{compat, Moving, MovingInput};
+
+/// How the mean of one accumulator relates to another's, from
+/// [`Moving::compare`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison {
+    /// Ordering of this mean relative to the other.
+    pub ordering: Option<Ordering>,
+    /// This mean minus the other.
+    pub difference: f64,
+    /// The difference as a fraction of the other mean; `None` when that
+    /// mean is zero.
+    pub relative_difference: Option<f64>,
+    /// Combined standard error of both means; `None` until each side has at
+    /// least two values.
+    pub standard_error: Option<f64>,
+    /// Whether the difference exceeds the combined standard error.
+    pub significant: bool,
+}
+
+impl<T> Moving<T>
+where
+    T: MovingInput,
+{
+    /// Compares this mean against `other`'s.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    /// use core::cmp::Ordering;
+    ///
+    /// let mut before: Moving<u32> = Moving::new();
+    /// let mut after: Moving<u32> = Moving::new();
+    /// for value in [10, 11, 9, 10] {
+    ///     before.add(value);
+    ///     after.add(value + 5);
+    /// }
+    /// let comparison = after.compare(&before);
+    /// assert_eq!(comparison.ordering, Some(Ordering::Greater));
+    /// assert_eq!(comparison.difference, 5.0);
+    /// assert_eq!(comparison.relative_difference, Some(0.5));
+    /// assert!(comparison.significant);
+    /// ```
+    pub fn compare<U>(&self, other: &Moving<U>) -> Comparison
+    where
+        U: MovingInput,
+    {
+        let difference = self.mean() - other.mean();
+        let relative_difference = (other.mean() != 0.0).then(|| difference / other.mean().abs());
+        let standard_error = self
+            .standard_error()
+            .zip(other.standard_error())
+            .map(|(a, b)| compat::sqrt(a * a + b * b));
+        Comparison {
+            ordering: self.mean().partial_cmp(&other.mean()),
+            difference,
+            relative_difference,
+            standard_error,
+            significant: standard_error.is_some_and(|error| difference.abs() > error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlapping_means_are_not_significant() {
+        let mut a: Moving<f64> = Moving::new();
+        let mut b: Moving<f64> = Moving::new();
+        for value in [0.0, 10.0, 0.0, 10.0] {
+            a.add(value);
+        }
+        for value in [1.0, 11.0, 1.0, 11.0] {
+            b.add(value);
+        }
+        let comparison = a.compare(&b);
+        assert_eq!(comparison.ordering, Some(Ordering::Less));
+        assert_eq!(comparison.difference, -1.0);
+        assert_eq!(comparison.relative_difference, Some(-1.0 / 6.0));
+        assert!(!comparison.significant);
+    }
+
+    #[test]
+    fn needs_two_values_for_standard_error() {
+        let mut a: Moving<i32> = Moving::new();
+        let b: Moving<i32> = Moving::new();
+        a.add(3);
+        let comparison = a.compare(&b);
+        assert_eq!(comparison.standard_error, None);
+        assert_eq!(comparison.relative_difference, None);
+        assert!(!comparison.significant);
+    }
+}
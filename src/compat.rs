@@ -0,0 +1,153 @@
+//! Stand-ins for the parts of `std` that are missing without the `std`
+//! feature.
+
+#[cfg(feature = "std")]
+pub(crate) use std::collections::HashMap as Map;
+
+#[cfg(not(feature = "std"))]
+pub(crate) use alloc::collections::BTreeMap as Map;
+
+#[cfg(feature = "std")]
+pub(crate) use std::time::Instant;
+
+/// There is no clock without `std`, so no timestamp can ever exist.
+#[cfg(not(feature = "std"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum Instant {}
+
+#[cfg(not(feature = "std"))]
+impl Instant {
+    pub(crate) fn saturating_duration_since(&self, _earlier: Instant) -> core::time::Duration {
+        match *self {}
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn sqrt(value: f64) -> f64 {
+    value.sqrt()
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn floor(value: f64) -> f64 {
+    value.floor()
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn round(value: f64) -> f64 {
+    value.round()
+}
+
+/// Newton's method from an estimate that halves the exponent.
+#[cfg(not(feature = "std"))]
+pub(crate) fn sqrt(value: f64) -> f64 {
+    if value.is_nan() || value < 0.0 {
+        return f64::NAN;
+    }
+    if value == 0.0 || value.is_infinite() {
+        return value;
+    }
+    let mut root = f64::from_bits((value.to_bits() >> 1) + (1023 << 51));
+    for _ in 0..8 {
+        root = (root + value / root) / 2.0;
+    }
+    root
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn floor(value: f64) -> f64 {
+    // Beyond 2^52 every f64 is already a whole number.
+    if !value.is_finite() || value.abs() >= 4_503_599_627_370_496.0 {
+        return value;
+    }
+    let truncated = value as i64 as f64;
+    if truncated > value {
+        truncated - 1.0
+    } else {
+        truncated
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn round(value: f64) -> f64 {
+    if value < 0.0 {
+        -floor(-value + 0.5)
+    } else {
+        floor(value + 0.5)
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn exp2(value: f64) -> f64 {
+    value.exp2()
+}
+
+/// `2^value`, splitting off the whole part as an exponent and taking the
+/// fraction from the Taylor series of `e^(fraction * ln 2)`.
+#[cfg(not(feature = "std"))]
+pub(crate) fn exp2(value: f64) -> f64 {
+    if value.is_nan() {
+        return value;
+    }
+    if value >= 1024.0 {
+        return f64::INFINITY;
+    }
+    if value < -1075.0 {
+        return 0.0;
+    }
+    let whole = floor(value);
+    let x = (value - whole) * core::f64::consts::LN_2;
+    let (mut term, mut sum) = (1.0, 1.0);
+    for n in 1..20 {
+        term *= x / n as f64;
+        sum += term;
+    }
+    let mut scale = 1.0;
+    let (factor, steps) = if whole < 0.0 {
+        (0.5, -whole as u32)
+    } else {
+        (2.0, whole as u32)
+    };
+    for _ in 0..steps {
+        scale *= factor;
+    }
+    sum * scale
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn log2(value: f64) -> f64 {
+    value.log2()
+}
+
+/// `log2(value)` from the binary exponent plus `ln` of the mantissa, taken
+/// from the series of `2 * atanh((m - 1) / (m + 1))`.
+#[cfg(not(feature = "std"))]
+pub(crate) fn log2(value: f64) -> f64 {
+    if value.is_nan() || value < 0.0 {
+        return f64::NAN;
+    }
+    if value == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if value.is_infinite() {
+        return value;
+    }
+    let (value, offset) = if value < f64::MIN_POSITIVE {
+        (value * 4_503_599_627_370_496.0, -52.0)
+    } else {
+        (value, 0.0)
+    };
+    let bits = value.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as f64 - 1023.0;
+    let mantissa = f64::from_bits((bits & ((1 << 52) - 1)) | (1023 << 52));
+    let z = (mantissa - 1.0) / (mantissa + 1.0);
+    let (mut term, mut sum) = (z, 0.0);
+    for n in 0..30 {
+        sum += term / (2 * n + 1) as f64;
+        term *= z * z;
+    }
+    exponent + offset + 2.0 * sum / core::f64::consts::LN_2
+}
+
+pub(crate) fn ceil(value: f64) -> f64 {
+    -floor(-value)
+}
@@ -0,0 +1,157 @@
+use core::ops::Deref;
+
+use crate::MovingInput;
+
+/// Average over the last `N` samples, held in an inline array so adding a
+/// sample never allocates.
+///
+/// ```rust
+/// use moving_average::ConstWindow;
+///
+/// let mut window: ConstWindow<u16, 3> = ConstWindow::new();
+/// for value in [1, 2, 3, 10] {
+///     window.add(value);
+/// }
+/// assert_eq!(*window, 5.0);
+/// assert_eq!(window.min(), Some(2.0));
+/// assert_eq!(window.max(), Some(10.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConstWindow<T, const N: usize> {
+    values: [f64; N],
+    next: usize,
+    len: usize,
+    sum: f64,
+    mean: f64,
+    evictions: usize,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T, const N: usize> Default for ConstWindow<T, N> {
+    fn default() -> Self {
+        Self {
+            values: [0.0; N],
+            next: 0,
+            len: 0,
+            sum: 0.0,
+            mean: 0.0,
+            evictions: 0,
+            phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<T, const N: usize> ConstWindow<T, N>
+where
+    T: MovingInput,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, value: T) {
+        if N == 0 {
+            return;
+        }
+        let value = T::to_f64(value);
+        if self.len == N {
+            self.sum -= self.values[self.next];
+            self.evictions += 1;
+        } else {
+            self.len += 1;
+        }
+        self.values[self.next] = value;
+        self.next = (self.next + 1) % N;
+        self.sum += value;
+        // Re-add from scratch once per window turnover so rounding error
+        // from the running subtraction cannot build up.
+        if self.evictions >= N {
+            self.evictions = 0;
+            self.sum = self.iter().sum();
+        }
+        self.mean = self.sum / self.len as f64;
+    }
+
+    /// Samples in the window, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = f64> + '_ {
+        let start = if self.len == N { self.next } else { 0 };
+        (0..self.len).map(move |i| self.values[(start + i) % N])
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Number of samples currently in the window.
+    pub fn count(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Smallest sample in the window.
+    pub fn min(&self) -> Option<f64> {
+        self.iter().reduce(f64::min)
+    }
+
+    /// Largest sample in the window.
+    pub fn max(&self) -> Option<f64> {
+        self.iter().reduce(f64::max)
+    }
+}
+
+impl<T, const N: usize> Deref for ConstWindow<T, N> {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.mean
+    }
+}
+
+impl<T, const N: usize> core::fmt::Display for ConstWindow<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.mean)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_around() {
+        let mut window: ConstWindow<i32, 2> = ConstWindow::new();
+        assert_eq!(window.min(), None);
+        window.add(4);
+        assert!(!window.is_full());
+        assert_eq!(window.mean(), 4.0);
+        for value in [6, 8, 10] {
+            window.add(value);
+        }
+        assert_eq!(window.iter().collect::<Vec<_>>(), vec![8.0, 10.0]);
+        assert_eq!(window.mean(), 9.0);
+        assert_eq!(window.count(), 2);
+    }
+
+    #[test]
+    fn zero_sized_window_stays_empty() {
+        let mut window: ConstWindow<f64, 0> = ConstWindow::new();
+        window.add(1.0);
+        assert_eq!(window.count(), 0);
+        assert_eq!(window.mean(), 0.0);
+    }
+
+    #[test]
+    fn no_drift_over_many_evictions() {
+        let mut window: ConstWindow<f64, 4> = ConstWindow::new();
+        for i in 0..100_000 {
+            window.add(if i % 2 == 0 { 1e12 } else { 0.1 });
+        }
+        for _ in 0..4 {
+            window.add(0.1);
+        }
+        assert_eq!(window.mean(), 0.1);
+    }
+}
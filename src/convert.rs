@@ -0,0 +1,207 @@
+use crate::{compat, Moving, MovingInput};
+
+/// How a statistic is rounded when converted to an integer type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Rounding {
+    /// To the nearest integer, halves away from zero.
+    #[default]
+    Nearest,
+    /// Towards negative infinity.
+    Down,
+    /// Towards positive infinity.
+    Up,
+    /// Dropping the fraction.
+    TowardZero,
+}
+
+impl Rounding {
+    pub fn apply(self, value: f64) -> f64 {
+        match self {
+            Rounding::Nearest => compat::round(value),
+            Rounding::Down => compat::floor(value),
+            Rounding::Up => compat::ceil(value),
+            Rounding::TowardZero if value < 0.0 => compat::ceil(value),
+            Rounding::TowardZero => compat::floor(value),
+        }
+    }
+}
+
+/// Types a statistic can be converted into by
+/// [`Moving::mean_as`] and friends.
+///
+/// Implemented for the primitive numeric types: integers round the value
+/// and are `None` when it is NaN or out of their range, floats ignore the
+/// rounding. Implement it for decimal or fixed-point types to read
+/// statistics in them directly.
+///
+/// ```rust
+/// use moving_average::{FromStatistic, Moving, Rounding};
+///
+/// /// Hundredths of a unit.
+/// #[derive(Debug, PartialEq)]
+/// struct Cents(i64);
+///
+/// impl FromStatistic for Cents {
+///     fn from_statistic(value: f64, rounding: Rounding) -> Option<Self> {
+///         i64::from_statistic(value * 100.0, rounding).map(Cents)
+///     }
+/// }
+///
+/// let mut price: Moving<f64> = Moving::new();
+/// price.add(1.25);
+/// price.add(1.35);
+/// assert_eq!(price.mean_as::<Cents>(), Some(Cents(130)));
+/// ```
+pub trait FromStatistic: Sized {
+    fn from_statistic(value: f64, rounding: Rounding) -> Option<Self>;
+}
+
+macro_rules! from_statistic_int {
+    ($($ty:ty),*) => {
+        $(
+            impl FromStatistic for $ty {
+                fn from_statistic(value: f64, rounding: Rounding) -> Option<Self> {
+                    let value = rounding.apply(value);
+                    // `MAX as f64 + 1.0` is exact or rounds to the next
+                    // power of two, which is out of range either way.
+                    (value >= <$ty>::MIN as f64 && value < <$ty>::MAX as f64 + 1.0)
+                        .then_some(value as $ty)
+                }
+            }
+        )*
+    };
+}
+
+from_statistic_int!(usize, isize, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+impl FromStatistic for f32 {
+    fn from_statistic(value: f64, _rounding: Rounding) -> Option<Self> {
+        Some(value as f32)
+    }
+}
+
+impl FromStatistic for f64 {
+    fn from_statistic(value: f64, _rounding: Rounding) -> Option<Self> {
+        Some(value)
+    }
+}
+
+/// Number-like types that can be averaged and that statistics can be read
+/// back as, such as fixed-point or units-of-measure wrappers. Implemented
+/// for every type implementing both [`MovingInput`] and [`FromStatistic`].
+///
+/// ```rust
+/// use moving_average::{FromStatistic, Moving, MovingInput, Rounding};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// struct Celsius(f64);
+///
+/// impl MovingInput for Celsius {
+///     fn to_f64(self) -> f64 {
+///         self.0
+///     }
+/// }
+///
+/// impl FromStatistic for Celsius {
+///     fn from_statistic(value: f64, _rounding: Rounding) -> Option<Self> {
+///         Some(Celsius(value))
+///     }
+/// }
+///
+/// let mut room: Moving<Celsius> = Moving::new();
+/// room.add(Celsius(20.0));
+/// room.add(Celsius(23.0));
+/// assert_eq!(room.mean_value(), Some(Celsius(21.5)));
+/// ```
+pub trait MovingValue: MovingInput + FromStatistic {}
+
+impl<T: MovingInput + FromStatistic> MovingValue for T {}
+
+impl<T> Moving<T>
+where
+    T: MovingValue,
+{
+    /// The mean in the value type, rounded to the nearest integer for
+    /// integer types; `None` before any value was added or if it does
+    /// not fit.
+    pub fn mean_value(&self) -> Option<T> {
+        (self.count() > 0).then(|| self.mean_as()).flatten()
+    }
+
+    /// The [`mode`](Self::mode) in the value type.
+    pub fn mode_value(&self) -> Option<T> {
+        self.mode_as()
+    }
+}
+
+impl<T> Moving<T>
+where
+    T: MovingInput,
+{
+    /// The mean converted to `F`, rounded to the nearest integer for
+    /// integer types; `None` if it does not fit.
+    ///
+    /// ```rust
+    /// use moving_average::{Moving, Rounding};
+    ///
+    /// let mut moving: Moving<u32> = Moving::new();
+    /// moving.add_slice(&[1, 2]);
+    /// assert_eq!(moving.mean_as::<u8>(), Some(2));
+    /// assert_eq!(moving.mean_as_rounded::<u8>(Rounding::Down), Some(1));
+    /// assert_eq!(moving.mean_as::<f32>(), Some(1.5));
+    /// ```
+    pub fn mean_as<F: FromStatistic>(&self) -> Option<F> {
+        self.mean_as_rounded(Rounding::Nearest)
+    }
+
+    pub fn mean_as_rounded<F: FromStatistic>(&self, rounding: Rounding) -> Option<F> {
+        F::from_statistic(self.mean(), rounding)
+    }
+
+    /// The [`mode`](Self::mode) converted to `F`.
+    pub fn mode_as<F: FromStatistic>(&self) -> Option<F> {
+        F::from_statistic(self.mode()?, Rounding::Nearest)
+    }
+
+    /// The [`median`](Self::median) converted to `F`.
+    pub fn median_as<F: FromStatistic>(&self) -> Option<F> {
+        F::from_statistic(self.median()?, Rounding::Nearest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_round_and_check_range() {
+        assert_eq!(i8::from_statistic(-2.5, Rounding::Nearest), Some(-3));
+        assert_eq!(i8::from_statistic(-2.5, Rounding::TowardZero), Some(-2));
+        assert_eq!(i8::from_statistic(-2.5, Rounding::Down), Some(-3));
+        assert_eq!(i8::from_statistic(2.1, Rounding::Up), Some(3));
+        assert_eq!(u8::from_statistic(255.4, Rounding::Nearest), Some(255));
+        assert_eq!(u8::from_statistic(255.5, Rounding::Nearest), None);
+        assert_eq!(u8::from_statistic(-0.4, Rounding::Nearest), Some(0));
+        assert_eq!(i64::from_statistic(9.3e18, Rounding::Nearest), None);
+        assert_eq!(u32::from_statistic(f64::NAN, Rounding::Nearest), None);
+    }
+
+    #[test]
+    fn converts_mode_and_median() {
+        let mut moving: Moving<f64> = Moving::new().with_mode_tracking().with_median();
+        assert_eq!(moving.mode_as::<u16>(), None);
+        moving.add_slice(&[3.0, 3.0, 10.0]);
+        assert_eq!(moving.mode_as::<u16>(), Some(3));
+        assert_eq!(moving.median_as::<i32>(), Some(3));
+        assert_eq!(moving.mean_as::<u64>(), Some(5));
+        assert_eq!(moving.mode_value(), Some(3.0));
+    }
+
+    #[test]
+    fn mean_in_the_value_type() {
+        let mut moving: Moving<i16> = Moving::new();
+        assert_eq!(moving.mean_value(), None);
+        moving.add_slice(&[-4, -5]);
+        assert_eq!(moving.mean_value(), Some(-5));
+    }
+}
@@ -0,0 +1,125 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::mode::key;
+
+/// Approximate value frequencies in fixed memory.
+///
+/// Each value is counted in one cell per row, chosen by a per-row hash; the
+/// estimate is the smallest of those cells. Collisions only ever add to a
+/// cell, so estimates never undercount, and wider rows make overcounting
+/// less likely.
+///
+/// ```rust
+/// use moving_average::CountMinSketch;
+///
+/// let mut sketch = CountMinSketch::new(256, 4);
+/// for reading in 0..10_000 {
+///     sketch.add(reading as f64 / 10.0);
+/// }
+/// sketch.add(1.5);
+/// assert!(sketch.estimate(1.5) >= 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CountMinSketch {
+    width: usize,
+    depth: usize,
+    cells: Vec<usize>,
+}
+
+/// SplitMix64 finalizer, a cheap well-mixing hash of a 64-bit key.
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+impl CountMinSketch {
+    /// `width` cells in each of `depth` rows; both are at least one.
+    pub fn new(width: usize, depth: usize) -> Self {
+        let width = width.max(1);
+        let depth = depth.max(1);
+        Self {
+            width,
+            depth,
+            cells: vec![0; width * depth],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    fn cells_of(&self, value: f64) -> impl Iterator<Item = usize> + '_ {
+        let key = key(value);
+        (0..self.depth).map(move |row| {
+            let seed = (row as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            row * self.width + (mix(key ^ seed) % self.width as u64) as usize
+        })
+    }
+
+    pub fn add(&mut self, value: f64) {
+        self.add_count(value, 1);
+    }
+
+    pub fn add_count(&mut self, value: f64, occurrences: usize) {
+        let cells: Vec<usize> = self.cells_of(value).collect();
+        for cell in cells {
+            self.cells[cell] += occurrences;
+        }
+    }
+
+    /// Takes back one occurrence of `value`, which must have been added.
+    pub fn remove(&mut self, value: f64) {
+        let cells: Vec<usize> = self.cells_of(value).collect();
+        for cell in cells {
+            self.cells[cell] = self.cells[cell].saturating_sub(1);
+        }
+    }
+
+    /// Upper bound on how often `value` was added.
+    pub fn estimate(&self, value: f64) -> usize {
+        self.cells_of(value)
+            .map(|cell| self.cells[cell])
+            .min()
+            .unwrap_or(0)
+    }
+
+    pub fn reset(&mut self) {
+        self.cells.fill(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn never_undercounts() {
+        let mut sketch = CountMinSketch::new(8, 3);
+        for value in 0..100 {
+            for _ in 0..value % 5 {
+                sketch.add(value as f64);
+            }
+        }
+        for value in 0..100 {
+            assert!(sketch.estimate(value as f64) >= value % 5);
+        }
+        assert_eq!(sketch.estimate(-0.0), sketch.estimate(0.0));
+    }
+
+    #[test]
+    fn remove_and_reset() {
+        let mut sketch = CountMinSketch::new(1024, 4);
+        sketch.add_count(2.5, 3);
+        sketch.remove(2.5);
+        assert_eq!(sketch.estimate(2.5), 2);
+        sketch.reset();
+        assert_eq!(sketch.estimate(2.5), 0);
+        assert_eq!(CountMinSketch::new(0, 0).width(), 1);
+    }
+}
@@ -0,0 +1,247 @@
+use core::ops::Deref;
+
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use crate::{compat, Forecast, ForecastErrors, MovingInput};
+
+/// Exponential moving average: each sample moves the mean by `alpha` of
+/// its distance from it, so older samples fade out geometrically.
+///
+/// ```rust
+/// use moving_average::Ema;
+///
+/// let mut ema: Ema<f64> = Ema::new(0.5);
+/// ema.add(10.0);
+/// ema.add(20.0);
+/// assert_eq!(*ema, 15.0);
+/// ema.add(20.0);
+/// assert_eq!(*ema, 17.5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ema<T> {
+    alpha: f64,
+    count: usize,
+    mean: f64,
+    errors: ForecastErrors,
+    #[cfg(feature = "std")]
+    half_life: Option<(Duration, Option<Instant>)>,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> Ema<T>
+where
+    T: MovingInput,
+{
+    /// `alpha` is the weight of the newest sample, clamped to `0.0..=1.0`;
+    /// larger values react faster. The first sample seeds the mean.
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha: if alpha.is_nan() {
+                1.0
+            } else {
+                alpha.clamp(0.0, 1.0)
+            },
+            count: 0,
+            mean: 0.0,
+            errors: ForecastErrors::new(),
+            #[cfg(feature = "std")]
+            half_life: None,
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Weight that halves every `samples` samples: a sample's influence on
+    /// the mean is half as large `samples` adds later.
+    ///
+    /// ```rust
+    /// use moving_average::Ema;
+    ///
+    /// let mut ema: Ema<f64> = Ema::from_half_life(1.0);
+    /// assert_eq!(ema.alpha(), 0.5);
+    /// ```
+    pub fn from_half_life(samples: f64) -> Self {
+        Self::new(1.0 - compat::exp2(-1.0 / samples))
+    }
+
+    /// Weight that halves every `half_life` of time, however many samples
+    /// arrive in between. Samples are timestamped on [`add`](Self::add) or
+    /// by the caller with [`add_at`](Self::add_at).
+    ///
+    /// Samples at the same instant as the previous one do not move the
+    /// mean. A zero half-life keeps only the latest sample that arrived
+    /// later than the one before.
+    ///
+    /// ```rust
+    /// use moving_average::Ema;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let start = Instant::now();
+    /// let mut ema: Ema<f64> = Ema::from_half_life_duration(Duration::from_secs(60));
+    /// ema.add_at(0.0, start);
+    /// ema.add_at(100.0, start + Duration::from_secs(60));
+    /// assert_eq!(ema.mean(), 50.0);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_half_life_duration(half_life: Duration) -> Self {
+        Self {
+            half_life: Some((half_life, None)),
+            ..Self::new(1.0)
+        }
+    }
+
+    pub fn add(&mut self, value: T) {
+        #[cfg(feature = "std")]
+        if self.half_life.is_some() {
+            return self.add_at(value, Instant::now());
+        }
+        self.update(T::to_f64(value), self.alpha);
+    }
+
+    /// Adds a value observed at `at`; only a half-life given as a duration
+    /// uses the timestamp.
+    #[cfg(feature = "std")]
+    pub fn add_at(&mut self, value: T, at: Instant) {
+        let alpha = match &mut self.half_life {
+            Some((half_life, last_at)) => {
+                let elapsed = last_at.map_or(Duration::ZERO, |last_at| {
+                    at.saturating_duration_since(last_at)
+                });
+                *last_at = Some(last_at.map_or(at, |last_at| last_at.max(at)));
+                if elapsed.is_zero() {
+                    0.0
+                } else if half_life.is_zero() {
+                    1.0
+                } else {
+                    1.0 - compat::exp2(-elapsed.as_secs_f64() / half_life.as_secs_f64())
+                }
+            }
+            None => self.alpha,
+        };
+        self.update(T::to_f64(value), alpha);
+    }
+
+    fn update(&mut self, value: f64, alpha: f64) {
+        self.count += 1;
+        if self.count == 1 {
+            self.mean = value;
+            return;
+        }
+        self.errors.record(self.mean, value);
+        self.mean += alpha * (value - self.mean);
+    }
+
+    /// The fixed weight of each sample; `1.0` for a half-life given as a
+    /// duration, where the weight depends on the time between samples.
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl<T> Forecast for Ema<T> {
+    fn forecast_at(&self, horizon: usize) -> Option<f64> {
+        (horizon > 0 && self.count > 0).then_some(self.mean)
+    }
+
+    fn errors(&self) -> &ForecastErrors {
+        &self.errors
+    }
+}
+
+impl<T> Deref for Ema<T> {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.mean
+    }
+}
+
+impl<T> core::fmt::Display for Ema<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.mean)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_samples_dominate() {
+        let mut ema: Ema<u32> = Ema::new(0.2);
+        for _ in 0..100 {
+            ema.add(0);
+        }
+        for _ in 0..20 {
+            ema.add(100);
+        }
+        assert!(ema.mean() > 98.0);
+        assert_eq!(ema.count(), 120);
+    }
+
+    #[test]
+    fn alpha_is_clamped() {
+        assert_eq!(Ema::<f64>::new(2.0).alpha(), 1.0);
+        assert_eq!(Ema::<f64>::new(-1.0).alpha(), 0.0);
+        let mut frozen: Ema<f64> = Ema::new(0.0);
+        frozen.add(5.0);
+        frozen.add(50.0);
+        assert_eq!(frozen.mean(), 5.0);
+    }
+
+    #[test]
+    fn half_life_in_samples() {
+        let mut ema: Ema<f64> = Ema::from_half_life(10.0);
+        ema.add(100.0);
+        for _ in 0..10 {
+            ema.add(0.0);
+        }
+        assert!((ema.mean() - 50.0).abs() < 1e-9);
+        assert_eq!(Ema::<f64>::from_half_life(0.0).alpha(), 1.0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn half_life_in_time() {
+        let start = Instant::now();
+        let mut ema: Ema<f64> = Ema::from_half_life_duration(Duration::from_secs(1));
+        ema.add_at(100.0, start);
+        ema.add_at(0.0, start + Duration::from_secs(2));
+        assert!((ema.mean() - 25.0).abs() < 1e-9);
+        // A burst at the same instant does not move the mean.
+        ema.add_at(1000.0, start + Duration::from_secs(2));
+        assert!((ema.mean() - 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn zero_half_life_keeps_the_latest() {
+        let start = Instant::now();
+        let mut ema: Ema<f64> = Ema::from_half_life_duration(Duration::ZERO);
+        ema.add_at(4.0, start);
+        ema.add_at(8.0, start);
+        assert_eq!(ema.mean(), 4.0);
+        ema.add_at(6.0, start + Duration::from_millis(1));
+        assert_eq!(ema.mean(), 6.0);
+        ema.add_at(9.0, start);
+        assert_eq!(ema.mean(), 6.0);
+    }
+
+    #[test]
+    fn forecasts_the_mean() {
+        let mut ema: Ema<f64> = Ema::new(0.5);
+        assert_eq!(ema.forecast(), None);
+        ema.add(4.0);
+        ema.add(8.0);
+        assert_eq!(ema.forecast_n(2), vec![6.0, 6.0]);
+        assert_eq!(ema.errors().mae(), Some(4.0));
+    }
+}
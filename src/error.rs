@@ -0,0 +1,90 @@
+use crate::ThresholdBreach;
+
+/// Errors returned by the fallible ingestion methods.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MovingError {
+    /// A sample was missing and the [`MissingPolicy`](crate::MissingPolicy)
+    /// is `Error`.
+    MissingValue,
+    /// A sample fell outside the configured valid range.
+    OutOfRange,
+    /// A sample was dropped by a filter stage.
+    Filtered,
+    /// The accumulator is frozen and ignoring samples.
+    Frozen,
+    /// A value was removed while no values were left.
+    Empty,
+    /// The value was recorded and the mean is now past the configured
+    /// [`Threshold`](crate::Threshold).
+    ThresholdReached(ThresholdBreach),
+    /// A weight was not a positive, finite number.
+    InvalidWeight,
+    /// A weight other than one was given while a statistic that counts each
+    /// value once, such as the median, is enabled.
+    UnweightedStatistic,
+    /// A sample was dropped as an outlier.
+    Outlier,
+    /// A sample was NaN or infinite and the
+    /// [`NonFinitePolicy`](crate::NonFinitePolicy) is `Reject`.
+    NonFinite,
+    /// The count of values is at `usize::MAX` and the
+    /// [`OverflowPolicy`](crate::OverflowPolicy) is `Error`.
+    CountOverflow,
+    /// A vector had a different number of elements than the accumulator
+    /// averages.
+    DimensionMismatch { expected: usize, actual: usize },
+}
+
+impl core::fmt::Display for MovingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let message = match self {
+            MovingError::MissingValue => "sample is missing",
+            MovingError::OutOfRange => "sample is outside the valid range",
+            MovingError::Filtered => "sample was dropped by a filter",
+            MovingError::Frozen => "accumulator is frozen",
+            MovingError::Empty => "no values left to remove",
+            MovingError::ThresholdReached(breach) => {
+                return write!(
+                    f,
+                    "threshold reached: {:?} of {} is past {} after {}",
+                    breach.statistic, breach.observed, breach.bound, breach.value
+                );
+            }
+            MovingError::InvalidWeight => "weight is not positive and finite",
+            MovingError::UnweightedStatistic => "an enabled statistic cannot weigh samples",
+            MovingError::Outlier => "sample is an outlier",
+            MovingError::NonFinite => "sample is not finite",
+            MovingError::CountOverflow => "count of values would overflow",
+            MovingError::DimensionMismatch { expected, actual } => {
+                return write!(f, "expected {expected} dimensions, got {actual}");
+            }
+        };
+        f.write_str(message)
+    }
+}
+
+impl core::error::Error for MovingError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Statistic;
+
+    #[test]
+    fn readable_messages() {
+        let error: Box<dyn std::error::Error> = Box::new(MovingError::OutOfRange);
+        assert_eq!(error.to_string(), "sample is outside the valid range");
+        assert_eq!(MovingError::Empty.to_string(), "no values left to remove");
+        let breach = ThresholdBreach {
+            statistic: Statistic::Mean,
+            observed: 12.5,
+            bound: 10.0,
+            mean: 12.5,
+            value: 20.0,
+        };
+        assert_eq!(
+            MovingError::ThresholdReached(breach).to_string(),
+            "threshold reached: Mean of 12.5 is past 10 after 20"
+        );
+    }
+}
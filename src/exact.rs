@@ -0,0 +1,315 @@
+use core::ops::Deref;
+
+/// Integers whose mean [`ExactMoving`] computes without rounding the sum.
+pub trait ExactInteger: Copy {
+    /// Two's complement value sign-extended to 256 bits, least significant
+    /// limb first.
+    fn to_limbs(self) -> [u64; 4];
+
+    /// Builds the value from a sign and a magnitude known to fit.
+    fn from_magnitude(negative: bool, magnitude: u128) -> Self;
+}
+
+macro_rules! exact_signed {
+    ($($ty:ty),*) => {
+        $(
+            impl ExactInteger for $ty {
+                fn to_limbs(self) -> [u64; 4] {
+                    let value = self as i128;
+                    let extension = if value < 0 { u64::MAX } else { 0 };
+                    [value as u64, (value >> 64) as u64, extension, extension]
+                }
+
+                fn from_magnitude(negative: bool, magnitude: u128) -> Self {
+                    if negative {
+                        0u128.wrapping_sub(magnitude) as i128 as Self
+                    } else {
+                        magnitude as Self
+                    }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! exact_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl ExactInteger for $ty {
+                fn to_limbs(self) -> [u64; 4] {
+                    let value = self as u128;
+                    [value as u64, (value >> 64) as u64, 0, 0]
+                }
+
+                fn from_magnitude(_negative: bool, magnitude: u128) -> Self {
+                    magnitude as Self
+                }
+            }
+        )*
+    };
+}
+
+exact_signed!(i8, i16, i32, i64, i128, isize);
+exact_unsigned!(u8, u16, u32, u64, u128, usize);
+
+/// Mean of integers kept as an exact 256-bit sum and a count, so even
+/// `u128` and `i128` values are not rounded before averaging.
+///
+/// ```rust
+/// use moving_average::ExactMoving;
+///
+/// let mut exact: ExactMoving<u128> = ExactMoving::new();
+/// exact.add(u128::MAX);
+/// exact.add(u128::MAX - 2);
+/// assert_eq!(exact.mean_truncated(), Some(u128::MAX - 1));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ExactMoving<T> {
+    sum: [u64; 4],
+    count: u64,
+    mean: f64,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> ExactMoving<T>
+where
+    T: ExactInteger,
+{
+    pub fn new() -> Self {
+        Self {
+            sum: [0; 4],
+            count: 0,
+            mean: 0.0,
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    pub fn add(&mut self, value: T) {
+        let mut carry = false;
+        for (limb, addend) in self.sum.iter_mut().zip(value.to_limbs()) {
+            let (partial, first) = limb.overflowing_add(addend);
+            let (total, second) = partial.overflowing_add(carry as u64);
+            *limb = total;
+            carry = first || second;
+        }
+        self.count += 1;
+        if let Some((negative, quotient, remainder)) = self.divide() {
+            let magnitude = to_f64(quotient, remainder, self.count);
+            self.mean = if negative { -magnitude } else { magnitude };
+        }
+    }
+
+    /// Sign and magnitude of the sum.
+    fn magnitude(&self) -> (bool, [u64; 4]) {
+        let negative = self.sum[3] >> 63 == 1;
+        let mut magnitude = self.sum;
+        if negative {
+            let mut carry = true;
+            for limb in &mut magnitude {
+                let (total, overflow) = (!*limb).overflowing_add(carry as u64);
+                *limb = total;
+                carry = overflow;
+            }
+        }
+        (negative, magnitude)
+    }
+
+    /// Divides `magnitude` by the count, returning the quotient and the
+    /// remainder; `None` if the quotient does not fit in 128 bits.
+    fn divide_magnitude(&self, magnitude: [u64; 4]) -> Option<(u128, u64)> {
+        let mut quotient = [0u64; 4];
+        let mut remainder = 0u128;
+        for i in (0..4).rev() {
+            let current = (remainder << 64) | magnitude[i] as u128;
+            quotient[i] = (current / self.count as u128) as u64;
+            remainder = current % self.count as u128;
+        }
+        if quotient[2] != 0 || quotient[3] != 0 {
+            return None;
+        }
+        let quotient = (quotient[1] as u128) << 64 | quotient[0] as u128;
+        Some((quotient, remainder as u64))
+    }
+
+    /// Sign, and magnitude as quotient and remainder, of the sum divided by
+    /// the count.
+    fn divide(&self) -> Option<(bool, u128, u64)> {
+        if self.count == 0 {
+            return None;
+        }
+        let (negative, magnitude) = self.magnitude();
+        let (quotient, remainder) = self.divide_magnitude(magnitude)?;
+        Some((negative, quotient, remainder))
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The mean, rounded once to the nearest `f64`.
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// The exact mean with its fraction truncated towards zero.
+    pub fn mean_truncated(&self) -> Option<T> {
+        let (negative, quotient, _) = self.divide()?;
+        Some(T::from_magnitude(negative, quotient))
+    }
+    /// The mean as a decimal with `digits` fractional digits, returned as
+    /// the integer `mean * 10^digits` rounded half away from zero. No binary
+    /// floating point is involved, so amounts such as cents average exactly
+    /// to the requested precision. `None` when empty or when the result does
+    /// not fit in an `i128`.
+    ///
+    /// ```rust
+    /// use moving_average::ExactMoving;
+    ///
+    /// let mut cents: ExactMoving<i64> = ExactMoving::new();
+    /// for price in [1999, 2999, 1000] {
+    ///     cents.add(price);
+    /// }
+    /// // 1999.33 cents
+    /// assert_eq!(cents.mean_scaled(2), Some(199_933));
+    /// ```
+    pub fn mean_scaled(&self, digits: u32) -> Option<i128> {
+        if self.count == 0 {
+            return None;
+        }
+        let (negative, mut magnitude) = self.magnitude();
+        for _ in 0..digits {
+            let mut carry = 0u128;
+            for limb in &mut magnitude {
+                let product = *limb as u128 * 10 + carry;
+                *limb = product as u64;
+                carry = product >> 64;
+            }
+            if carry != 0 || magnitude[3] >> 63 == 1 {
+                return None;
+            }
+        }
+        let (mut quotient, remainder) = self.divide_magnitude(magnitude)?;
+        if remainder as u128 * 2 >= self.count as u128 {
+            quotient = quotient.checked_add(1)?;
+        }
+        if negative {
+            0i128.checked_sub_unsigned(quotient)
+        } else {
+            i128::try_from(quotient).ok()
+        }
+    }
+}
+
+/// `quotient + remainder / count` rounded once to the nearest `f64`.
+///
+/// Long division extends the quotient with fraction bits until it holds at
+/// least 66 significant bits; whatever fraction is left can then only break
+/// a tie, so it is folded into the lowest bit before the single rounding.
+fn to_f64(quotient: u128, remainder: u64, count: u64) -> f64 {
+    let count = count as u128;
+    let mut mantissa = quotient;
+    let mut remainder = remainder as u128;
+    let mut exponent = 0i32;
+    while mantissa >> 65 == 0 && remainder != 0 {
+        remainder <<= 1;
+        let bit = remainder >= count;
+        if bit {
+            remainder -= count;
+        }
+        mantissa = mantissa << 1 | bit as u128;
+        exponent -= 1;
+    }
+    if remainder != 0 {
+        mantissa |= 1;
+    }
+    // The fraction is at least 1/count, so the exponent stays well inside
+    // the normal range and the power of two is exact.
+    mantissa as f64 * f64::from_bits(((1023 + exponent) as u64) << 52)
+}
+
+impl<T> Deref for ExactMoving<T> {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.mean
+    }
+}
+
+impl<T> core::fmt::Display for ExactMoving<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.mean)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn large_unsigned_values() {
+        let mut exact: ExactMoving<u128> = ExactMoving::new();
+        assert_eq!(exact.mean_truncated(), None);
+        let base = 1u128 << 100;
+        for offset in [1, 2, 3, 6] {
+            exact.add(base + offset);
+        }
+        assert_eq!(exact.mean_truncated(), Some(base + 3));
+        assert_eq!(exact.count(), 4);
+        assert_eq!(exact.mean(), base as f64);
+    }
+
+    #[test]
+    fn signed_extremes() {
+        let mut exact: ExactMoving<i128> = ExactMoving::new();
+        exact.add(i128::MIN);
+        exact.add(i128::MIN);
+        assert_eq!(exact.mean_truncated(), Some(i128::MIN));
+        exact.add(i128::MAX);
+        exact.add(i128::MAX);
+        assert_eq!(exact.mean_truncated(), Some(0));
+        assert_eq!(exact.mean(), -0.5);
+    }
+
+    #[test]
+    fn small_integers() {
+        let mut exact: ExactMoving<i8> = ExactMoving::new();
+        for value in [-7, -2] {
+            exact.add(value);
+        }
+        assert_eq!(exact.mean(), -4.5);
+        assert_eq!(exact.mean_truncated(), Some(-4));
+        assert_eq!(exact.mean_scaled(0), Some(-5));
+        assert_eq!(exact.mean_scaled(3), Some(-4500));
+    }
+
+    #[test]
+    fn scaled_mean_limits() {
+        let mut exact: ExactMoving<u128> = ExactMoving::new();
+        assert_eq!(exact.mean_scaled(2), None);
+        exact.add(1);
+        exact.add(2);
+        exact.add(2);
+        assert_eq!(exact.mean_scaled(4), Some(16_667));
+        exact.add(u128::MAX);
+        assert_eq!(exact.mean_scaled(0), Some((1 << 126) + 1));
+        assert_eq!(exact.mean_scaled(1), None);
+    }
+
+    #[test]
+    fn mean_is_rounded_once() {
+        // 2^53 + 4/3: rounding the quotient 2^53 + 1 to even first would
+        // land on 2^53.
+        let mut exact: ExactMoving<i64> = ExactMoving::new();
+        for value in [1 << 53, (1 << 53) + 1, (1 << 53) + 3] {
+            exact.add(value);
+        }
+        assert_eq!(exact.mean(), 9_007_199_254_740_994.0);
+        let mut exact: ExactMoving<u8> = ExactMoving::new();
+        for value in [0, 0, 1] {
+            exact.add(value);
+        }
+        assert_eq!(exact.mean(), 1.0 / 3.0);
+        exact.add(255);
+        assert_eq!(exact.mean(), 64.0);
+    }
+}
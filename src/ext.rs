@@ -0,0 +1,209 @@
+use core::borrow::Borrow;
+
+use crate::{Ema, Moving, MovingInput, MovingSnapshot, WindowedMoving};
+
+/// Collects an iterator of values straight into one of the averages.
+///
+/// Works over owned values as well as references, so both
+/// `data.iter()` and `data.into_iter()` can be averaged; annotate the
+/// result type to pick the value type.
+///
+/// ```rust
+/// use moving_average::{Moving, MovingAverageExt, WindowedMoving};
+///
+/// let data = [10u32, 20, 30, 40];
+/// let moving: Moving<u32> = data.iter().moving_average();
+/// assert_eq!(moving, 25);
+///
+/// let windowed: WindowedMoving<u32> = data.into_iter().windowed_moving_average(2);
+/// assert_eq!(*windowed, 35.0);
+/// ```
+pub trait MovingAverageExt: Iterator + Sized {
+    fn moving_average<T>(self) -> Moving<T>
+    where
+        Self::Item: Borrow<T>,
+        T: MovingInput + Copy,
+    {
+        let mut moving = Moving::new();
+        moving.add_iter(self.map(|value| *value.borrow()));
+        moving
+    }
+
+    /// Average over the last `window` values.
+    fn windowed_moving_average<T>(self, window: usize) -> WindowedMoving<T>
+    where
+        Self::Item: Borrow<T>,
+        T: MovingInput + Copy,
+    {
+        let mut windowed = WindowedMoving::new(window);
+        for value in self {
+            windowed.add(*value.borrow());
+        }
+        windowed
+    }
+
+    /// Exponential moving average with smoothing factor `alpha`.
+    fn exponential_moving_average<T>(self, alpha: f64) -> Ema<T>
+    where
+        Self::Item: Borrow<T>,
+        T: MovingInput + Copy,
+    {
+        let mut ema = Ema::new(alpha);
+        for value in self {
+            ema.add(*value.borrow());
+        }
+        ema
+    }
+
+    /// Folds the values into `moving` as they are pulled, yielding the
+    /// statistics after each one.
+    ///
+    /// ```rust
+    /// use moving_average::{Moving, MovingAverageExt};
+    ///
+    /// let means: Vec<f64> = [2u32, 4, 9]
+    ///     .iter()
+    ///     .running_snapshots(Moving::<u32>::new())
+    ///     .map(|snapshot| snapshot.mean)
+    ///     .collect();
+    /// assert_eq!(means, [2.0, 3.0, 5.0]);
+    /// ```
+    fn running_snapshots<T>(self, moving: Moving<T>) -> RunningSnapshots<Self, T>
+    where
+        Self::Item: Borrow<T>,
+        T: MovingInput + Copy,
+    {
+        RunningSnapshots {
+            values: self,
+            moving,
+        }
+    }
+
+    /// Mean of the last `window` values at every position, `None` until the
+    /// window first fills; the shape dataframe libraries give a rolling mean
+    /// column. Columns exposed as slices can be passed by `iter()`.
+    ///
+    /// ```rust
+    /// use moving_average::MovingAverageExt;
+    ///
+    /// let column = [1.0, 3.0, 5.0, 7.0];
+    /// let rolling: Vec<Option<f64>> = column.iter().rolling_mean::<f64>(2).collect();
+    /// assert_eq!(rolling, [None, Some(2.0), Some(4.0), Some(6.0)]);
+    /// ```
+    fn rolling_mean<T>(self, window: usize) -> RollingMean<Self, T>
+    where
+        Self::Item: Borrow<T>,
+        T: MovingInput + Copy,
+    {
+        RollingMean {
+            values: self,
+            windowed: WindowedMoving::new(window),
+        }
+    }
+}
+
+impl<I: Iterator> MovingAverageExt for I {}
+
+/// Iterator returned by [`MovingAverageExt::running_snapshots`].
+#[derive(Debug, Clone)]
+pub struct RunningSnapshots<I, T> {
+    values: I,
+    moving: Moving<T>,
+}
+
+impl<I, T> RunningSnapshots<I, T> {
+    /// The accumulator holding every value pulled so far.
+    pub fn moving(&self) -> &Moving<T> {
+        &self.moving
+    }
+
+    pub fn into_moving(self) -> Moving<T> {
+        self.moving
+    }
+}
+
+impl<I, T> Iterator for RunningSnapshots<I, T>
+where
+    I: Iterator,
+    I::Item: Borrow<T>,
+    T: MovingInput + Copy,
+{
+    type Item = MovingSnapshot;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.values.next()?;
+        self.moving.add(*value.borrow());
+        Some(self.moving.snapshot())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
+
+/// Iterator returned by [`MovingAverageExt::rolling_mean`].
+#[derive(Debug, Clone)]
+pub struct RollingMean<I, T> {
+    values: I,
+    windowed: WindowedMoving<T>,
+}
+
+impl<I, T> Iterator for RollingMean<I, T>
+where
+    I: Iterator,
+    I::Item: Borrow<T>,
+    T: MovingInput + Copy,
+{
+    type Item = Option<f64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.values.next()?;
+        self.windowed.add(*value.borrow());
+        Some(self.windowed.is_full().then(|| self.windowed.mean()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_owned_and_borrowed() {
+        let data = vec![1.0, 2.0, 6.0];
+        let borrowed: Moving<f64> = data.iter().moving_average();
+        let owned: Moving<f64> = data.clone().into_iter().moving_average();
+        assert_eq!(borrowed.mean(), 3.0);
+        assert_eq!(owned.count(), 3);
+        let ema: Ema<f64> = data.iter().exponential_moving_average(0.5);
+        assert_eq!(ema.mean(), 3.75);
+        let empty: Moving<i8> = core::iter::empty::<i8>().moving_average();
+        assert_eq!(empty.count(), 0);
+    }
+
+    #[test]
+    fn running_snapshots_keep_the_accumulator() {
+        let mut running = [5i32, -5, 3]
+            .into_iter()
+            .running_snapshots(Moving::new().with_mode_tracking());
+        assert_eq!(running.next().map(|snapshot| snapshot.max), Some(Some(5.0)));
+        assert_eq!(
+            running.by_ref().last().map(|snapshot| snapshot.count),
+            Some(3)
+        );
+        let moving = running.into_moving();
+        assert_eq!(moving.min(), Some(-5.0));
+        assert_eq!(moving.mean(), 1.0);
+    }
+
+    #[test]
+    fn rolling_mean_waits_for_a_full_window() {
+        let rolling: Vec<Option<f64>> = [4u8, 8, 0, 2].into_iter().rolling_mean(3).collect();
+        assert_eq!(rolling, [None, None, Some(4.0), Some(10.0 / 3.0)]);
+        let single: Vec<Option<f64>> = [5i32, -5].iter().rolling_mean::<i32>(1).collect();
+        assert_eq!(single, [Some(5.0), Some(-5.0)]);
+    }
+}
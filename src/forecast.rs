@@ -0,0 +1,101 @@
+use alloc::vec::Vec;
+
+use crate::compat;
+
+/// Predictions from the smoothing types for samples that have not arrived yet.
+///
+/// ```rust
+/// use moving_average::{Forecast, HoltWinters};
+///
+/// let mut holt_winters: HoltWinters<f64> = HoltWinters::new(2);
+/// for i in 0..20 {
+///     holt_winters.add([1.0, 3.0][i % 2]);
+/// }
+/// assert_eq!(holt_winters.forecast_n(2).len(), 2);
+/// ```
+pub trait Forecast {
+    /// Prediction `horizon` steps after the most recent sample; a horizon of
+    /// zero has no prediction.
+    fn forecast_at(&self, horizon: usize) -> Option<f64>;
+
+    /// Prediction for the next sample.
+    fn forecast(&self) -> Option<f64> {
+        self.forecast_at(1)
+    }
+
+    /// Predictions for the next `k` samples.
+    fn forecast_n(&self, k: usize) -> Vec<f64> {
+        (1..=k)
+            .map_while(|horizon| self.forecast_at(horizon))
+            .collect()
+    }
+
+    /// Accuracy of the one-step forecasts made so far.
+    fn errors(&self) -> &ForecastErrors;
+}
+
+/// Running accuracy of one-step forecasts against the samples that followed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ForecastErrors {
+    count: usize,
+    absolute: f64,
+    squared: f64,
+    percentage: f64,
+    percentage_count: usize,
+}
+
+impl ForecastErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the residual between a forecast and the sample that arrived.
+    pub fn record(&mut self, forecast: f64, actual: f64) {
+        let residual = actual - forecast;
+        self.count += 1;
+        self.absolute += residual.abs();
+        self.squared += residual * residual;
+        if actual != 0.0 {
+            self.percentage_count += 1;
+            self.percentage += (residual / actual).abs();
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Mean absolute error.
+    pub fn mae(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.absolute / self.count as f64)
+    }
+
+    /// Mean absolute percentage error, as a fraction; samples equal to zero
+    /// are left out.
+    pub fn mape(&self) -> Option<f64> {
+        (self.percentage_count > 0).then(|| self.percentage / self.percentage_count as f64)
+    }
+
+    /// Root mean squared error.
+    pub fn rmse(&self) -> Option<f64> {
+        (self.count > 0).then(|| compat::sqrt(self.squared / self.count as f64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_metrics() {
+        let mut errors = ForecastErrors::new();
+        assert_eq!(errors.mae(), None);
+        errors.record(8.0, 10.0);
+        errors.record(6.0, 4.0);
+        errors.record(1.0, 0.0);
+        assert_eq!(errors.count(), 3);
+        assert_eq!(errors.mae(), Some(5.0 / 3.0));
+        assert_eq!(errors.rmse(), Some(3.0_f64.sqrt()));
+        assert_eq!(errors.mape(), Some((0.2 + 0.5) / 2.0));
+    }
+}
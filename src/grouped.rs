@@ -0,0 +1,186 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use crate::{Moving, MovingInput, MovingRegistry};
+
+/// Statistics per key alongside the combined total, from `(key, value)`
+/// pairs, with queries that compare the groups.
+///
+/// ```rust
+/// use moving_average::GroupedMoving;
+///
+/// let mut latency: GroupedMoving<&str, u32> = GroupedMoving::new();
+/// latency.extend([("eu", 20), ("us", 80), ("eu", 40), ("ap", 50)]);
+///
+/// assert_eq!(latency.mean_of("eu"), Some(30.0));
+/// assert_eq!(latency.top_keys_by_mean(2), vec![(&"us", 80.0), (&"ap", 50.0)]);
+/// assert_eq!(latency.total().mean(), 47.5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GroupedMoving<K, T> {
+    groups: MovingRegistry<K, T>,
+    total: Moving<T>,
+}
+
+impl<K, T> Default for GroupedMoving<K, T>
+where
+    K: Ord + Clone,
+    T: MovingInput + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, T> GroupedMoving<K, T>
+where
+    K: Ord + Clone,
+    T: MovingInput + Copy,
+{
+    pub fn new() -> Self {
+        Self::with_template(Moving::new())
+    }
+
+    /// Each group and the total start as an empty copy of `template`.
+    pub fn with_template(template: Moving<T>) -> Self {
+        let groups = MovingRegistry::with_template(template);
+        let total = groups.template().clone();
+        Self { groups, total }
+    }
+
+    pub fn add(&mut self, key: K, value: T) {
+        self.groups.add(&key, value);
+        self.total.add(value);
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&Moving<T>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.groups.get(key)
+    }
+
+    pub fn mean_of<Q>(&self, key: &Q) -> Option<f64>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).map(Moving::mean)
+    }
+
+    pub fn count_of<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).map_or(0, Moving::count)
+    }
+
+    /// The `n` groups with the highest mean, highest first; equal means
+    /// are ordered by key.
+    pub fn top_keys_by_mean(&self, n: usize) -> Vec<(&K, f64)> {
+        let mut means = self.means();
+        means.sort_by(|a, b| b.1.total_cmp(&a.1));
+        means.truncate(n);
+        means
+    }
+
+    /// The `n` groups with the lowest mean, lowest first.
+    pub fn bottom_keys_by_mean(&self, n: usize) -> Vec<(&K, f64)> {
+        let mut means = self.means();
+        means.sort_by(|a, b| a.1.total_cmp(&b.1));
+        means.truncate(n);
+        means
+    }
+
+    fn means(&self) -> Vec<(&K, f64)> {
+        self.groups
+            .iter()
+            .filter(|(_, moving)| moving.count() > 0)
+            .map(|(key, moving)| (key, moving.mean()))
+            .collect()
+    }
+
+    /// Statistics over every value, whatever its key.
+    pub fn total(&self) -> &Moving<T> {
+        &self.total
+    }
+
+    pub fn groups(&self) -> &MovingRegistry<K, T> {
+        &self.groups
+    }
+
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    pub fn reset(&mut self) {
+        self.groups.clear();
+        self.total.reset();
+    }
+}
+
+impl<K, T> Extend<(K, T)> for GroupedMoving<K, T>
+where
+    K: Ord + Clone,
+    T: MovingInput + Copy,
+{
+    fn extend<I: IntoIterator<Item = (K, T)>>(&mut self, pairs: I) {
+        for (key, value) in pairs {
+            self.add(key, value);
+        }
+    }
+}
+
+impl<K, T> FromIterator<(K, T)> for GroupedMoving<K, T>
+where
+    K: Ord + Clone,
+    T: MovingInput + Copy,
+{
+    fn from_iter<I: IntoIterator<Item = (K, T)>>(pairs: I) -> Self {
+        let mut grouped = Self::new();
+        grouped.extend(pairs);
+        grouped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_groups_and_keeps_totals() {
+        let grouped: GroupedMoving<u8, f64> = [(1, 5.0), (2, 1.0), (3, 5.0), (1, 5.0), (2, 3.0)]
+            .into_iter()
+            .collect();
+        assert_eq!(grouped.len(), 3);
+        assert_eq!(grouped.count_of(&1), 2);
+        assert_eq!(grouped.count_of(&9), 0);
+        assert_eq!(grouped.mean_of(&9), None);
+        assert_eq!(
+            grouped.top_keys_by_mean(5),
+            vec![(&1, 5.0), (&3, 5.0), (&2, 2.0)]
+        );
+        assert_eq!(grouped.bottom_keys_by_mean(1), vec![(&2, 2.0)]);
+        assert_eq!(grouped.total().count(), 5);
+        assert_eq!(grouped.total().max(), Some(5.0));
+    }
+
+    #[test]
+    fn groups_share_the_template() {
+        let mut grouped: GroupedMoving<&str, i32> =
+            GroupedMoving::with_template(Moving::new().with_valid_range(0.0, 10.0));
+        grouped.add("a", 20);
+        grouped.add("a", 4);
+        assert_eq!(grouped.count_of("a"), 1);
+        assert_eq!(grouped.total().count(), 1);
+        grouped.reset();
+        assert!(grouped.is_empty());
+        assert_eq!(grouped.total().count(), 0);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{Forecast, ForecastErrors, MovingInput};
+
+const DEFAULT_ALPHA: f64 = 0.5;
+const DEFAULT_BETA: f64 = 0.1;
+
+/// Holt's linear smoothing: an exponentially smoothed level plus a smoothed
+/// trend, for streams that drift without a seasonal pattern.
+///
+/// The first sample seeds the level and the second the trend.
+///
+/// ```rust
+/// use moving_average::{Forecast, Holt};
+///
+/// let mut holt: Holt<f64> = Holt::new(0.5, 0.3);
+/// for i in 0..100 {
+///     holt.add(10.0 + 2.0 * i as f64);
+/// }
+/// assert!((holt.trend().unwrap() - 2.0).abs() < 1e-9);
+/// assert!((holt.forecast_at(2).unwrap() - 212.0).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Holt<T> {
+    alpha: f64,
+    beta: f64,
+    count: usize,
+    level: f64,
+    trend: f64,
+    errors: ForecastErrors,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> Default for Holt<T>
+where
+    T: MovingInput,
+{
+    fn default() -> Self {
+        Self::new(DEFAULT_ALPHA, DEFAULT_BETA)
+    }
+}
+
+impl<T> Holt<T>
+where
+    T: MovingInput,
+{
+    /// Smoothing factors for the level and the trend, each clamped to
+    /// `0.0..=1.0`; larger values react faster.
+    pub fn new(alpha: f64, beta: f64) -> Self {
+        let clamp = |factor: f64| {
+            if factor.is_nan() {
+                1.0
+            } else {
+                factor.clamp(0.0, 1.0)
+            }
+        };
+        Self {
+            alpha: clamp(alpha),
+            beta: clamp(beta),
+            count: 0,
+            level: 0.0,
+            trend: 0.0,
+            errors: ForecastErrors::new(),
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    pub fn add(&mut self, value: T) {
+        let value = T::to_f64(value);
+        self.count += 1;
+        match self.count {
+            1 => self.level = value,
+            2 => {
+                self.errors.record(self.level, value);
+                self.trend = value - self.level;
+                self.level = value;
+            }
+            _ => {
+                let previous_level = self.level;
+                self.errors.record(previous_level + self.trend, value);
+                self.level =
+                    self.alpha * value + (1.0 - self.alpha) * (previous_level + self.trend);
+                self.trend =
+                    self.beta * (self.level - previous_level) + (1.0 - self.beta) * self.trend;
+            }
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn level(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.level)
+    }
+
+    /// Change in the level per sample; available from the second sample.
+    pub fn trend(&self) -> Option<f64> {
+        (self.count > 1).then_some(self.trend)
+    }
+}
+
+impl<T> Forecast for Holt<T> {
+    fn forecast_at(&self, horizon: usize) -> Option<f64> {
+        (horizon > 0 && self.count > 0).then_some(self.level + horizon as f64 * self.trend)
+    }
+
+    fn errors(&self) -> &ForecastErrors {
+        &self.errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeds_level_then_trend() {
+        let mut holt: Holt<u32> = Holt::default();
+        assert_eq!(holt.forecast(), None);
+        holt.add(4);
+        assert_eq!(holt.level(), Some(4.0));
+        assert_eq!(holt.trend(), None);
+        assert_eq!(holt.forecast(), Some(4.0));
+        holt.add(10);
+        assert_eq!(holt.trend(), Some(6.0));
+        assert_eq!(holt.forecast_n(2), [16.0, 22.0]);
+        holt.add(16);
+        assert_eq!(holt.level(), Some(16.0));
+        assert_eq!(holt.errors().count(), 2);
+        assert_eq!(holt.errors().mae(), Some(3.0));
+    }
+
+    #[test]
+    fn follows_a_change_in_slope() {
+        let mut holt: Holt<f64> = Holt::new(0.6, 0.4);
+        for i in 0..50 {
+            holt.add(i as f64);
+        }
+        for i in 0..200 {
+            holt.add(50.0 - 3.0 * i as f64);
+        }
+        assert!((holt.trend().unwrap() + 3.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,255 @@
+use alloc::vec::Vec;
+
+use crate::{Forecast, ForecastErrors, MovingInput, Seasonality};
+
+const DEFAULT_ALPHA: f64 = 0.5;
+const DEFAULT_BETA: f64 = 0.1;
+const DEFAULT_GAMMA: f64 = 0.1;
+
+/// Online additive Holt-Winters decomposition of a stream into level, trend,
+/// seasonal and residual components.
+///
+/// The first `period` samples seed the seasonal profile; components are
+/// available once that first season is complete.
+///
+/// ```rust
+/// use moving_average::HoltWinters;
+///
+/// let mut holt_winters: HoltWinters<f64> = HoltWinters::new(4);
+/// for i in 0..40 {
+///     holt_winters.add(10.0 + [1.0, -1.0, 2.0, -2.0][i % 4]);
+/// }
+/// assert!((holt_winters.level().unwrap() - 10.0).abs() < 1e-9);
+/// assert!(holt_winters.residual().unwrap().abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct HoltWinters<T> {
+    period: usize,
+    alpha: f64,
+    beta: f64,
+    gamma: f64,
+    count: usize,
+    level: f64,
+    trend: f64,
+    seasonal: Vec<f64>,
+    residual: Option<f64>,
+    errors: ForecastErrors,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> HoltWinters<T>
+where
+    T: MovingInput,
+{
+    /// A period of zero is treated as one, i.e. no seasonality.
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            alpha: DEFAULT_ALPHA,
+            beta: DEFAULT_BETA,
+            gamma: DEFAULT_GAMMA,
+            count: 0,
+            level: 0.0,
+            trend: 0.0,
+            seasonal: Vec::with_capacity(period),
+            residual: None,
+            errors: ForecastErrors::new(),
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Configures the period from a detector, if it has found one.
+    pub fn from_seasonality<S>(seasonality: &Seasonality<S>) -> Option<Self>
+    where
+        S: MovingInput,
+    {
+        seasonality.period().map(Self::new)
+    }
+
+    /// Smoothing factors for the level, trend and seasonal components, each
+    /// clamped to `0.0..=1.0`; larger values react faster.
+    pub fn with_smoothing(mut self, alpha: f64, beta: f64, gamma: f64) -> Self {
+        let clamp = |factor: f64| {
+            if factor.is_nan() {
+                1.0
+            } else {
+                factor.clamp(0.0, 1.0)
+            }
+        };
+        self.alpha = clamp(alpha);
+        self.beta = clamp(beta);
+        self.gamma = clamp(gamma);
+        self
+    }
+
+    pub fn add(&mut self, value: T) {
+        let value = T::to_f64(value);
+        let slot = self.count % self.period;
+        self.count += 1;
+
+        if self.count <= self.period {
+            self.seasonal.push(value);
+            if self.count == self.period {
+                self.level = self.seasonal.iter().sum::<f64>() / self.period as f64;
+                for component in &mut self.seasonal {
+                    *component -= self.level;
+                }
+                self.residual = Some(0.0);
+            }
+            return;
+        }
+
+        let previous_level = self.level;
+        let previous_trend = self.trend;
+        let season = self.seasonal[slot];
+
+        let forecast = previous_level + previous_trend + season;
+        self.residual = Some(value - forecast);
+        self.errors.record(forecast, value);
+        self.level =
+            self.alpha * (value - season) + (1.0 - self.alpha) * (previous_level + previous_trend);
+        self.trend = self.beta * (self.level - previous_level) + (1.0 - self.beta) * previous_trend;
+        self.seasonal[slot] = self.gamma * (value - self.level) + (1.0 - self.gamma) * season;
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.count >= self.period
+    }
+
+    pub fn level(&self) -> Option<f64> {
+        self.is_ready().then_some(self.level)
+    }
+
+    pub fn trend(&self) -> Option<f64> {
+        self.is_ready().then_some(self.trend)
+    }
+
+    /// Seasonal component of the most recent sample.
+    pub fn seasonal(&self) -> Option<f64> {
+        self.is_ready()
+            .then(|| self.seasonal[(self.count - 1) % self.period])
+    }
+
+    /// The full seasonal profile, indexed by position within the period.
+    pub fn seasonal_components(&self) -> Option<&[f64]> {
+        self.is_ready().then_some(self.seasonal.as_slice())
+    }
+
+    /// Difference between the most recent sample and what the model expected.
+    pub fn residual(&self) -> Option<f64> {
+        self.residual
+    }
+}
+
+impl<T> Forecast for HoltWinters<T> {
+    fn forecast_at(&self, horizon: usize) -> Option<f64> {
+        if horizon == 0 || self.count < self.period {
+            return None;
+        }
+        let slot = (self.count + horizon - 1) % self.period;
+        Some(self.level + horizon as f64 * self.trend + self.seasonal[slot])
+    }
+
+    fn errors(&self) -> &ForecastErrors {
+        &self.errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn not_ready_before_first_season() {
+        let mut holt_winters: HoltWinters<usize> = HoltWinters::new(3);
+        holt_winters.add(1);
+        holt_winters.add(2);
+        assert_eq!(holt_winters.level(), None);
+        assert_eq!(holt_winters.residual(), None);
+        holt_winters.add(3);
+        assert_eq!(holt_winters.level(), Some(2.0));
+        assert_eq!(
+            holt_winters.seasonal_components(),
+            Some(&[-1.0, 0.0, 1.0][..])
+        );
+    }
+
+    #[test]
+    fn tracks_trend() {
+        let mut holt_winters: HoltWinters<f64> = HoltWinters::new(4).with_smoothing(0.5, 0.3, 0.1);
+        for i in 0..400 {
+            holt_winters.add(2.0 * i as f64 + [3.0, -3.0, 1.0, -1.0][i % 4]);
+        }
+        assert!((holt_winters.trend().unwrap() - 2.0).abs() < 1e-3);
+        assert!(holt_winters.residual().unwrap().abs() < 1e-2);
+    }
+
+    #[test]
+    fn residual_flags_spike() {
+        let mut holt_winters: HoltWinters<f64> = HoltWinters::new(4);
+        for i in 0..40 {
+            holt_winters.add([1.0, 2.0, 3.0, 4.0][i % 4]);
+        }
+        holt_winters.add(50.0);
+        assert!(holt_winters.residual().unwrap() > 40.0);
+    }
+
+    #[test]
+    fn forecasts_next_season() {
+        let mut holt_winters: HoltWinters<f64> = HoltWinters::new(3);
+        assert_eq!(holt_winters.forecast(), None);
+        for i in 0..30 {
+            holt_winters.add([1.0, 2.0, 6.0][i % 3]);
+        }
+        let forecast = holt_winters.forecast_n(4);
+        for (predicted, expected) in forecast.iter().zip([1.0, 2.0, 6.0, 1.0]) {
+            assert!((predicted - expected).abs() < 1e-9);
+        }
+        assert_eq!(holt_winters.forecast_at(0), None);
+    }
+
+    #[test]
+    fn tracks_forecast_errors() {
+        let mut holt_winters: HoltWinters<f64> = HoltWinters::new(2);
+        for value in [1.0, 3.0, 1.0, 3.0] {
+            holt_winters.add(value);
+        }
+        assert_eq!(holt_winters.errors().count(), 2);
+        assert_eq!(holt_winters.errors().mae(), Some(0.0));
+        holt_winters.add(5.0);
+        assert_eq!(holt_winters.errors().mae(), Some(4.0 / 3.0));
+    }
+
+    #[test]
+    fn configured_from_seasonality() {
+        let mut seasonality: Seasonality<f64> = Seasonality::new(40);
+        for i in 0..40 {
+            seasonality.add([1.0, 5.0, 3.0, 0.0, 2.0][i % 5]);
+        }
+        let holt_winters: HoltWinters<f64> = HoltWinters::from_seasonality(&seasonality).unwrap();
+        assert_eq!(holt_winters.period(), 5);
+    }
+
+    #[test]
+    fn smoothing_is_clamped() {
+        let mut holt_winters: HoltWinters<f64> =
+            HoltWinters::new(2).with_smoothing(3.0, -1.0, f64::NAN);
+        assert_eq!(
+            (holt_winters.alpha, holt_winters.beta, holt_winters.gamma),
+            (1.0, 0.0, 1.0)
+        );
+        for i in 0..200 {
+            holt_winters.add([1.0, 3.0][i % 2]);
+        }
+        assert!((holt_winters.level().unwrap() - 2.0).abs() < 1e-9);
+    }
+}
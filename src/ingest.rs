@@ -0,0 +1,224 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::MovingError;
+
+/// Closure applied to each value before it reaches the statistics.
+#[derive(Clone)]
+pub(crate) struct Transform(Arc<dyn Fn(f64) -> f64 + Send + Sync>);
+
+impl Transform {
+    pub(crate) fn new(transform: impl Fn(f64) -> f64 + Send + Sync + 'static) -> Self {
+        Self(Arc::new(transform))
+    }
+
+    pub(crate) fn apply(&self, value: f64) -> f64 {
+        (self.0)(value)
+    }
+}
+
+impl core::fmt::Debug for Transform {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Transform(..)")
+    }
+}
+
+/// Predicate deciding whether a value is kept.
+#[derive(Clone)]
+pub(crate) struct Filter(Arc<dyn Fn(f64) -> bool + Send + Sync>);
+
+impl Filter {
+    pub(crate) fn new(filter: impl Fn(f64) -> bool + Send + Sync + 'static) -> Self {
+        Self(Arc::new(filter))
+    }
+
+    pub(crate) fn keeps(&self, value: f64) -> bool {
+        (self.0)(value)
+    }
+}
+
+impl core::fmt::Debug for Filter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Filter(..)")
+    }
+}
+
+/// How far from the centre of the values seen so far a value may fall
+/// before [`Moving::with_outlier_rejection`](crate::Moving::with_outlier_rejection)
+/// drops it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutlierRule {
+    /// More than `k` standard deviations from the mean.
+    StdDev(f64),
+    /// More than `k` median absolute deviations from the median; turns on
+    /// [`Moving::with_median`](crate::Moving::with_median) to keep the
+    /// values.
+    Mad(f64),
+}
+
+/// The kind of an ingestion stage, in [`StageReport`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StageKind {
+    /// Drops values outside a valid range.
+    Validate,
+    /// Drops values rejected by a predicate.
+    Filter,
+    /// Rewrites values; never drops any.
+    Transform,
+    /// Drops values far from the values seen so far.
+    Outlier,
+}
+
+/// How many values one ingestion stage has dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageReport {
+    pub kind: StageKind,
+    pub dropped: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Stage {
+    Validate(f64, f64),
+    Filter(Filter),
+    Transform(Transform),
+    Outlier(OutlierRule),
+}
+
+/// Ordered stages every value passes through before it is accumulated.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Pipeline {
+    stages: Vec<(Stage, usize)>,
+}
+
+impl Pipeline {
+    pub(crate) fn validate(&mut self, min: f64, max: f64) {
+        self.stages.push((Stage::Validate(min, max), 0));
+    }
+
+    pub(crate) fn filter(&mut self, filter: Filter) {
+        self.stages.push((Stage::Filter(filter), 0));
+    }
+
+    pub(crate) fn transform(&mut self, transform: Transform) {
+        self.stages.push((Stage::Transform(transform), 0));
+    }
+
+    pub(crate) fn outliers(&mut self, rule: OutlierRule) {
+        self.stages.push((Stage::Outlier(rule), 0));
+    }
+
+    /// Runs `value` through every stage, returning the value to accumulate.
+    /// `spread` gives the centre and scale an outlier rule measures against,
+    /// or `None` while there are too few values to judge.
+    pub(crate) fn run(
+        &mut self,
+        mut value: f64,
+        spread: impl Fn(OutlierRule) -> Option<(f64, f64)>,
+    ) -> Result<f64, MovingError> {
+        for (stage, dropped) in &mut self.stages {
+            match stage {
+                Stage::Validate(min, max) => {
+                    if !(*min..=*max).contains(&value) {
+                        *dropped += 1;
+                        return Err(MovingError::OutOfRange);
+                    }
+                }
+                Stage::Filter(filter) => {
+                    if !filter.keeps(value) {
+                        *dropped += 1;
+                        return Err(MovingError::Filtered);
+                    }
+                }
+                Stage::Transform(transform) => value = transform.apply(value),
+                Stage::Outlier(rule) => {
+                    let k = match rule {
+                        OutlierRule::StdDev(k) | OutlierRule::Mad(k) => *k,
+                    };
+                    if let Some((centre, scale)) = spread(*rule) {
+                        if (value - centre).abs() > k * scale {
+                            *dropped += 1;
+                            return Err(MovingError::Outlier);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(value)
+    }
+
+    pub(crate) fn dropped(&self) -> usize {
+        self.stages.iter().map(|(_, dropped)| dropped).sum()
+    }
+
+    pub(crate) fn reports(&self) -> Vec<StageReport> {
+        self.stages
+            .iter()
+            .map(|(stage, dropped)| StageReport {
+                kind: match stage {
+                    Stage::Validate(..) => StageKind::Validate,
+                    Stage::Filter(_) => StageKind::Filter,
+                    Stage::Transform(_) => StageKind::Transform,
+                    Stage::Outlier(_) => StageKind::Outlier,
+                },
+                dropped: *dropped,
+            })
+            .collect()
+    }
+
+    pub(crate) fn reset_counts(&mut self) {
+        for (_, dropped) in &mut self.stages {
+            *dropped = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_run_in_order() {
+        let mut pipeline = Pipeline::default();
+        pipeline.transform(Transform::new(|value| value * 10.0));
+        pipeline.validate(0.0, 50.0);
+        pipeline.filter(Filter::new(|value| value != 30.0));
+
+        assert_eq!(pipeline.run(2.0, |_| None), Ok(20.0));
+        assert_eq!(pipeline.run(6.0, |_| None), Err(MovingError::OutOfRange));
+        assert_eq!(pipeline.run(3.0, |_| None), Err(MovingError::Filtered));
+        assert_eq!(pipeline.dropped(), 2);
+        assert_eq!(
+            pipeline.reports(),
+            vec![
+                StageReport {
+                    kind: StageKind::Transform,
+                    dropped: 0
+                },
+                StageReport {
+                    kind: StageKind::Validate,
+                    dropped: 1
+                },
+                StageReport {
+                    kind: StageKind::Filter,
+                    dropped: 1
+                },
+            ]
+        );
+
+        pipeline.reset_counts();
+        assert_eq!(pipeline.dropped(), 0);
+    }
+
+    #[test]
+    fn outliers_need_a_spread() {
+        let mut pipeline = Pipeline::default();
+        pipeline.outliers(OutlierRule::StdDev(2.0));
+        assert_eq!(pipeline.run(100.0, |_| None), Ok(100.0));
+        assert_eq!(pipeline.run(13.0, |_| Some((10.0, 1.5))), Ok(13.0));
+        assert_eq!(
+            pipeline.run(13.5, |_| Some((10.0, 1.5))),
+            Err(MovingError::Outlier)
+        );
+        assert_eq!(pipeline.reports()[0].kind, StageKind::Outlier);
+    }
+}
@@ -0,0 +1,152 @@
+use core::f64::consts::{LN_2, PI};
+use core::ops::RangeInclusive;
+
+use crate::{compat, Moving, MovingInput};
+
+/// Inverse of the standard normal CDF, by Acklam's rational approximation
+/// (relative error below 1.2e-9).
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    let tail = |p: f64| {
+        let q = compat::sqrt(-2.0 * compat::log2(p) * LN_2);
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < 0.02425 {
+        tail(p)
+    } else if p > 1.0 - 0.02425 {
+        -tail(1.0 - p)
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// `tan(x)` for `|x| < pi / 2`, from the Taylor series of sine and cosine.
+fn tan(x: f64) -> f64 {
+    let (mut sin, mut cos) = (0.0, 0.0);
+    let (mut sin_term, mut cos_term) = (x, 1.0);
+    for n in 0..30 {
+        sin += sin_term;
+        cos += cos_term;
+        let k = 2.0 * n as f64;
+        sin_term *= -x * x / ((k + 2.0) * (k + 3.0));
+        cos_term *= -x * x / ((k + 1.0) * (k + 2.0));
+    }
+    sin / cos
+}
+
+/// Quantile `p` of Student's t distribution with `dof` degrees of freedom:
+/// exact for one and two, otherwise the Cornish-Fisher expansion around
+/// the normal quantile.
+fn t_quantile(p: f64, dof: f64) -> f64 {
+    if dof == 1.0 {
+        return tan(PI * (p - 0.5));
+    }
+    if dof == 2.0 {
+        return (2.0 * p - 1.0) * compat::sqrt(2.0 / (4.0 * p * (1.0 - p)));
+    }
+    let z = normal_quantile(p);
+    let z2 = z * z;
+    let g1 = (z2 + 1.0) * z / 4.0;
+    let g2 = ((5.0 * z2 + 16.0) * z2 + 3.0) * z / 96.0;
+    let g3 = (((3.0 * z2 + 19.0) * z2 + 17.0) * z2 - 15.0) * z / 384.0;
+    let g4 = ((((79.0 * z2 + 776.0) * z2 + 1482.0) * z2 - 1920.0) * z2 - 945.0) * z / 92160.0;
+    z + (g1 + (g2 + (g3 + g4 / dof) / dof) / dof) / dof
+}
+
+impl<T> Moving<T>
+where
+    T: MovingInput,
+{
+    /// Interval that contains the true mean with probability `level`, e.g.
+    /// `0.95`, from the standard error and Student's t distribution with
+    /// `count - 1` degrees of freedom. `None` with fewer than two values or
+    /// a level outside `0.0..1.0`.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut conversion: Moving<f64> = Moving::new();
+    /// for rate in [0.12, 0.10, 0.14, 0.11, 0.13] {
+    ///     conversion.add(rate);
+    /// }
+    /// let interval = conversion.confidence_interval(0.95).unwrap();
+    /// assert!(interval.contains(&0.12));
+    /// assert!((interval.end() - 0.1396).abs() < 1e-3);
+    /// ```
+    pub fn confidence_interval(&self, level: f64) -> Option<RangeInclusive<f64>> {
+        if !(level > 0.0 && level < 1.0) {
+            return None;
+        }
+        let standard_error = self.standard_error()?;
+        let dof = (self.count() - 1) as f64;
+        let margin = t_quantile(0.5 + level / 2.0, dof) * standard_error;
+        Some(self.mean() - margin..=self.mean() + margin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantiles_match_tables() {
+        assert!((normal_quantile(0.975) - 1.959964).abs() < 1e-6);
+        assert!((normal_quantile(0.005) + 2.575829).abs() < 1e-6);
+        for (dof, expected) in [
+            (1.0, 12.706205),
+            (2.0, 4.302653),
+            (3.0, 3.182446),
+            (4.0, 2.776445),
+            (10.0, 2.228139),
+            (1000.0, 1.962339),
+        ] {
+            let t = t_quantile(0.975, dof);
+            assert!((t - expected).abs() / expected < 2e-3, "dof {dof}: {t}");
+        }
+    }
+
+    #[test]
+    fn needs_two_values_and_a_level() {
+        let mut moving_average: Moving<u32> = Moving::new();
+        moving_average.add(4);
+        assert_eq!(moving_average.confidence_interval(0.95), None);
+        moving_average.add(6);
+        assert_eq!(moving_average.confidence_interval(1.0), None);
+        let interval = moving_average.confidence_interval(0.5).unwrap();
+        // One degree of freedom: t = 1 at the 75th percentile.
+        assert!((interval.start() - 4.0).abs() < 1e-9);
+        assert!((interval.end() - 6.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,128 @@
+use alloc::string::String;
+use core::fmt::{self, Write};
+
+use crate::{Moving, MovingInput, Statistic, Threshold};
+
+impl<T> Moving<T>
+where
+    T: MovingInput,
+{
+    /// The statistics as a JSON object, e.g. for a debug endpoint.
+    ///
+    /// The keys are always `count`, `mean`, `mode`, `min`, `max` and
+    /// `threshold`, in that order. Statistics that are not available, and
+    /// non-finite values, are `null`; so is the threshold when none is
+    /// configured.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut latency: Moving<u32> = Moving::new().with_mode_tracking().with_threshold(100.0);
+    /// latency.add_slice(&[10, 30, 10, 30, 30]);
+    /// assert_eq!(
+    ///     latency.to_json(),
+    ///     r#"{"count":5,"mean":22,"mode":30,"min":10,"max":30,"threshold":{"statistic":"mean","upper":100}}"#
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        // Writing to a `String` cannot fail.
+        let _ = self.write_json(&mut json);
+        json
+    }
+
+    fn write_json(&self, out: &mut impl Write) -> fmt::Result {
+        write!(out, "{{\"count\":{},\"mean\":", self.count())?;
+        write_number(out, Some(self.mean()))?;
+        out.write_str(",\"mode\":")?;
+        write_number(out, self.mode())?;
+        out.write_str(",\"min\":")?;
+        write_number(out, self.min())?;
+        out.write_str(",\"max\":")?;
+        write_number(out, self.max())?;
+        out.write_str(",\"threshold\":")?;
+        match &self.threshold {
+            Some((statistic, threshold)) => write_threshold(out, *statistic, threshold)?,
+            None => out.write_str("null")?,
+        }
+        out.write_char('}')
+    }
+}
+
+fn write_threshold(
+    out: &mut impl Write,
+    statistic: Statistic,
+    threshold: &Threshold,
+) -> fmt::Result {
+    let statistic = match statistic {
+        Statistic::Mean => "mean",
+        Statistic::Count => "count",
+        Statistic::Mode => "mode",
+        Statistic::Variance => "variance",
+        Statistic::Last => "last",
+    };
+    write!(out, "{{\"statistic\":\"{statistic}\",")?;
+    match threshold {
+        Threshold::Upper(upper) => {
+            out.write_str("\"upper\":")?;
+            write_number(out, Some(*upper))?;
+        }
+        Threshold::Lower(lower) => {
+            out.write_str("\"lower\":")?;
+            write_number(out, Some(*lower))?;
+        }
+        Threshold::Range(range) => {
+            out.write_str("\"range\":[")?;
+            write_number(out, Some(range.start))?;
+            out.write_char(',')?;
+            write_number(out, Some(range.end))?;
+            out.write_char(']')?;
+        }
+        Threshold::Hysteresis { trigger, clear } => {
+            out.write_str("\"trigger\":")?;
+            write_number(out, Some(*trigger))?;
+            out.write_str(",\"clear\":")?;
+            write_number(out, Some(*clear))?;
+        }
+    }
+    out.write_char('}')
+}
+
+/// JSON has no infinities or NaN, so those are written as `null`.
+fn write_number(out: &mut impl Write, value: Option<f64>) -> fmt::Result {
+    match value {
+        Some(value) if value.is_finite() => write!(out, "{value}"),
+        _ => out.write_str("null"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_and_non_finite_values_are_null() {
+        let moving: Moving<f64> = Moving::new();
+        assert_eq!(
+            moving.to_json(),
+            r#"{"count":0,"mean":0,"mode":null,"min":null,"max":null,"threshold":null}"#
+        );
+        let mut moving: Moving<f64> = Moving::new().with_threshold_on(Statistic::Last, 1.0..2.5);
+        let _ = moving.add_with_result(f64::INFINITY);
+        assert_eq!(
+            moving.to_json(),
+            r#"{"count":1,"mean":null,"mode":null,"min":null,"max":null,"threshold":{"statistic":"last","range":[1,2.5]}}"#
+        );
+    }
+
+    #[test]
+    fn hysteresis_threshold() {
+        let moving: Moving<u8> = Moving::new().with_threshold(Threshold::Hysteresis {
+            trigger: 9.5,
+            clear: -1.0,
+        });
+        assert!(moving
+            .to_json()
+            .ends_with(r#""threshold":{"statistic":"mean","trigger":9.5,"clear":-1}}"#));
+    }
+}
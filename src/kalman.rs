@@ -0,0 +1,149 @@
+use crate::{Forecast, ForecastErrors, MovingInput};
+
+/// Scalar Kalman filter for a slowly drifting value observed through noise.
+///
+/// `process_noise` is how much the true value is expected to move between
+/// samples and `measurement_noise` is the variance of each reading; a larger
+/// ratio of the former to the latter follows new readings more closely.
+/// With both at zero the first reading is taken as exact and kept.
+///
+/// ```rust
+/// use moving_average::Kalman;
+///
+/// let mut kalman: Kalman<f64> = Kalman::new(1e-5, 0.25);
+/// for value in [10.2, 9.8, 10.1, 9.9, 10.0] {
+///     kalman.add(value);
+/// }
+/// assert!((kalman.mean() - 10.0).abs() < 0.1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Kalman<T> {
+    process_noise: f64,
+    measurement_noise: f64,
+    count: usize,
+    estimate: f64,
+    error: f64,
+    gain: f64,
+    errors: ForecastErrors,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> Kalman<T>
+where
+    T: MovingInput,
+{
+    pub fn new(process_noise: f64, measurement_noise: f64) -> Self {
+        Self {
+            process_noise,
+            measurement_noise,
+            count: 0,
+            estimate: 0.0,
+            error: 0.0,
+            gain: 0.0,
+            errors: ForecastErrors::new(),
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    pub fn add(&mut self, value: T) {
+        let value = T::to_f64(value);
+        self.count += 1;
+        if self.count == 1 {
+            self.estimate = value;
+            self.error = self.measurement_noise;
+            self.gain = 1.0;
+            return;
+        }
+
+        self.errors.record(self.estimate, value);
+        let predicted_error = self.error + self.process_noise;
+        let total_noise = predicted_error + self.measurement_noise;
+        // With no noise at all the estimate is already exact.
+        self.gain = if total_noise > 0.0 {
+            predicted_error / total_noise
+        } else {
+            0.0
+        };
+        self.estimate += self.gain * (value - self.estimate);
+        self.error = (1.0 - self.gain) * predicted_error;
+    }
+
+    /// Current estimate of the underlying value.
+    pub fn mean(&self) -> f64 {
+        self.estimate
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Variance of the current estimate.
+    pub fn variance(&self) -> f64 {
+        self.error
+    }
+
+    /// Weight the most recent reading received.
+    pub fn gain(&self) -> f64 {
+        self.gain
+    }
+}
+
+impl<T> Forecast for Kalman<T> {
+    fn forecast_at(&self, horizon: usize) -> Option<f64> {
+        (horizon > 0 && self.count > 0).then_some(self.estimate)
+    }
+
+    fn errors(&self) -> &ForecastErrors {
+        &self.errors
+    }
+}
+
+impl<T> core::fmt::Display for Kalman<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.estimate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_sample_is_the_estimate() {
+        let mut kalman: Kalman<i32> = Kalman::new(0.1, 1.0);
+        assert_eq!(kalman.forecast(), None);
+        kalman.add(4);
+        assert_eq!(kalman.mean(), 4.0);
+        assert_eq!(kalman.variance(), 1.0);
+        assert_eq!(kalman.forecast(), Some(4.0));
+    }
+
+    #[test]
+    fn zero_process_noise_is_the_cumulative_mean() {
+        let mut kalman: Kalman<f64> = Kalman::new(0.0, 1.0);
+        for value in [2.0, 4.0, 6.0, 8.0] {
+            kalman.add(value);
+        }
+        assert!((kalman.mean() - 5.0).abs() < 1e-12);
+        assert!((kalman.gain() - 0.25).abs() < 1e-12);
+        assert_eq!(kalman.errors().count(), 3);
+    }
+
+    #[test]
+    fn high_process_noise_follows_readings() {
+        let mut kalman: Kalman<f64> = Kalman::new(1e6, 1.0);
+        kalman.add(0.0);
+        kalman.add(100.0);
+        assert!((kalman.mean() - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn zero_noise_keeps_the_first_reading() {
+        let mut kalman: Kalman<f64> = Kalman::new(0.0, 0.0);
+        kalman.add(4.0);
+        kalman.add(8.0);
+        assert_eq!(kalman.mean(), 4.0);
+        assert_eq!(kalman.gain(), 0.0);
+        assert_eq!(kalman.variance(), 0.0);
+    }
+}
@@ -5,6 +5,67 @@
 //! ## Features
 //!
 //! - Calculate moving average in an ergonomic way.
+//! - Average even `u128` and `i128` values without rounding with [`ExactMoving`].
+//! - Average latencies directly as `Moving<Duration>`.
+//! - Average every field of a struct at once with `#[derive(MovingStats)]`
+//!   (feature `derive`).
+//! - Read statistics as integers, `f32` or your own numeric types with
+//!   [`Moving::mean_as`].
+//! - Get geometric and harmonic means alongside the arithmetic one, and
+//!   trimmed means that ignore the extremes.
+//! - Put error bars on the mean with [`Moving::confidence_interval`].
+//! - Keep per-minute, per-hour and per-day statistics side by side with
+//!   [`RollUp`].
+//! - Fill gaps in timestamped streams by zero-filling, carrying the last
+//!   value forward or interpolating, with [`GapPolicy`].
+//! - Turn irregular timestamped values into fixed-interval averages with
+//!   [`Resampler`].
+//! - Measure throughput in values per second with [`Moving::rate`].
+//! - Flag anomalous values by their z-score with [`Moving::add_scored`].
+//! - Be told when the mean passes an upper, lower or range [`Threshold`].
+//! - Hook logging or tracing into threshold crossings, anomalies and
+//!   rejections with [`Moving::with_observer`].
+//! - Average any iterator directly with [`MovingAverageExt`], or follow the
+//!   statistics value by value with [`RunningSnapshots`].
+//! - Compute a rolling mean column, such as a dataframe column, with
+//!   [`MovingAverageExt::rolling_mean`].
+//! - Average over only the most recent samples with [`WindowedMoving`], or
+//!   over a span of time with [`TimeWindowedMoving`]. [`ConstWindow`] does
+//!   so without allocating.
+//! - Weigh each position of a window differently with [`WeightedWindow`].
+//! - Follow how two streams move together with [`MovingPair`].
+//! - Average vectors such as positions or embeddings element-wise with
+//!   [`MovingVec`].
+//! - Estimate percentiles in constant memory with [`Quantile`].
+//! - Let old samples fade out with an exponential moving average, [`Ema`].
+//! - Detect the dominant period of a stream with [`Seasonality`].
+//! - Follow a drifting level and its trend with [`Holt`].
+//! - Decompose a stream into level, trend and seasonal parts with [`HoltWinters`].
+//! - Predict upcoming samples from the smoothing types via [`Forecast`].
+//! - Fit a least-squares [`Trend`] over positions or time, or an
+//!   outlier-resistant one with [`TheilSen`].
+//! - Smooth noisy readings with a scalar [`Kalman`] filter.
+//! - Keep one accumulator per label, such as per endpoint, with
+//!   [`MovingRegistry`].
+//! - Compare groups of `(key, value)` pairs, such as the regions with the
+//!   highest mean, with [`GroupedMoving`].
+//! - Keep separate statistics per value range with [`Stratified`].
+//! - Track frequencies and the mode of non-numeric keys with [`CategoricalMoving`].
+//! - Estimate frequencies of huge numbers of distinct values in fixed memory
+//!   with a `CountMinSketch` (feature `count-min`).
+//! - Share one accumulator between threads with [`SharedMoving`], or just a
+//!   mean without locking with `AtomicMoving` (on targets with 64-bit
+//!   atomics).
+//! - Average large slices on every core with [`Moving::par_from_slice`].
+//! - Spread adds from many threads over shards with [`ShardedMoving`] and
+//!   merge them on flush.
+//! - Feed an accumulator from many producers over a channel with
+//!   [`spawn_aggregator`].
+//! - Serve the statistics as JSON, e.g. on a debug endpoint, with
+//!   `Moving::to_json` (feature `json`).
+//! - Publish snapshots to a metrics system on a fixed cadence with
+//!   [`SharedMoving::report_every`].
+//! - Merge averages across machines without coordination with [`ReplicatedMoving`].
 //!
 //! ## Usage
 //!
@@ -32,40 +93,237 @@
 //! moving_average.add(10);
 //! moving_average.add(20);
 //! assert_eq!(moving_average, 15);
+//!
+//! moving_average.add(30);
+//! moving_average.remove(10);
+//! assert_eq!(moving_average, 25);
+//! ```
+//!
+//! ### Threads
+//!
+//! Every accumulator is plain owned data updated through `&mut self`, with
+//! no interior mutability, so it is `Send` and `Sync` whenever its value
+//! type is. Move it into a worker thread, or share it between threads
+//! behind a lock with [`SharedMoving`]; [`ShardedMoving`] spreads that
+//! lock over shards and, on targets with 64-bit atomics, `AtomicMoving`
+//! shares a mean without locking.
+//!
+//! ```rust
+//! use moving_average::Moving;
+//!
+//! let mut moving_average: Moving<u64> = Moving::new();
+//! let moving_average = std::thread::spawn(move || {
+//!     moving_average.add(7);
+//!     moving_average
+//! })
+//! .join()
+//! .unwrap();
+//! assert_eq!(moving_average, 7);
+//! ```
+//!
+//! ### `no_std`
+//!
+//! The default `std` feature can be turned off to build for targets
+//! without an operating system; only `alloc` is required. Timestamps,
+//! [`TimeWindowedMoving`], [`RollUp`], [`CategoricalMoving`], [`SharedMoving`]
+//! and [`spawn_aggregator`] need `std`.
+//!
+//! ```toml
+//! [dependencies]
+//! moving_average = { version = "0.1.0", default-features = false }
+//! ```
+//!
+//! ### Multi-field samples
+//!
+//! With the `derive` feature, `#[derive(MovingStats)]` on a struct with
+//! named numeric fields generates a `<Name>Stats` companion holding one
+//! [`Moving`] per field, fed by its `add(&Name)`. Fields marked
+//! `#[moving(skip)]` are left out.
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")]
+//! # fn main() {
+//! use moving_average::MovingStats;
+//!
+//! #[derive(MovingStats)]
+//! struct Sample {
+//!     cpu: f64,
+//!     memory: u64,
+//!     #[moving(skip)]
+//!     host: &'static str,
+//! }
+//!
+//! let mut stats = SampleStats::new();
+//! stats.add(&Sample { cpu: 0.5, memory: 300, host: "a" });
+//! stats.add(&Sample { cpu: 0.7, memory: 500, host: "b" });
+//! assert_eq!(stats.memory.mean(), 400.0);
+//! # }
+//! # #[cfg(not(feature = "derive"))]
+//! # fn main() {}
 //! ```
 
-use std::ops::{AddAssign, Deref};
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+// Lets the derive's `::moving_average` paths resolve inside this crate.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as moving_average;
+
+use alloc::vec::Vec;
+use core::ops::{AddAssign, Deref, SubAssign};
+use core::time::Duration;
+
+use compat::Instant;
+use compensated::CompensatedSum;
+
+use ingest::{Filter, Pipeline, Transform};
+use median::MedianTracker;
+use mode::ModeTracker;
+use observe::Observer;
+use window::Window;
+
+#[cfg(feature = "std")]
+mod aggregator;
+#[cfg(target_has_atomic = "64")]
+mod atomic;
+mod builder;
+#[cfg(feature = "std")]
+mod categorical;
+mod comparison;
+mod compat;
+mod compensated;
+mod const_window;
+mod convert;
+#[cfg(feature = "count-min")]
+mod count_min;
+mod ema;
+mod error;
+mod exact;
+mod ext;
+mod forecast;
+mod grouped;
+mod holt;
+mod holt_winters;
+mod ingest;
+mod interval;
+#[cfg(feature = "json")]
+mod json;
+mod kalman;
+mod median;
+mod mode;
+mod observe;
+mod outcome;
+mod pair;
+#[cfg(feature = "std")]
+mod parallel;
+mod quantile;
+mod registry;
+mod replicated;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "std")]
+mod resample;
+#[cfg(feature = "std")]
+mod rollup;
+mod score;
+mod seasonality;
+#[cfg(feature = "std")]
+mod sharded;
+#[cfg(feature = "std")]
+mod shared;
+mod snapshot;
+mod sparkline;
+mod state;
+mod stratified;
+mod theil_sen;
+mod threshold;
+#[cfg(feature = "std")]
+mod time_windowed;
+mod trend;
+mod vector;
+mod weighted_window;
+mod window;
+mod windowed;
+
+#[cfg(feature = "std")]
+pub use aggregator::{spawn_aggregator, Aggregator};
+#[cfg(target_has_atomic = "64")]
+pub use atomic::AtomicMoving;
+pub use builder::MovingBuilder;
+#[cfg(feature = "std")]
+pub use categorical::CategoricalMoving;
+pub use comparison::Comparison;
+pub use const_window::ConstWindow;
+pub use convert::{FromStatistic, MovingValue, Rounding};
+#[cfg(feature = "count-min")]
+pub use count_min::CountMinSketch;
+pub use ema::Ema;
+pub use error::MovingError;
+pub use exact::{ExactInteger, ExactMoving};
+pub use ext::{MovingAverageExt, RollingMean, RunningSnapshots};
+pub use forecast::{Forecast, ForecastErrors};
+pub use grouped::GroupedMoving;
+pub use holt::Holt;
+pub use holt_winters::HoltWinters;
+pub use ingest::{OutlierRule, StageKind, StageReport};
+pub use kalman::Kalman;
+pub use mode::ModeBackend;
+pub use observe::MovingEvent;
+pub use outcome::AddOutcome;
+pub use pair::MovingPair;
+pub use quantile::Quantile;
+pub use registry::MovingRegistry;
+pub use replicated::{ReplicaState, ReplicatedMoving};
+#[cfg(feature = "std")]
+pub use report::Reporter;
+#[cfg(feature = "std")]
+pub use resample::{resample, Resampled, Resampler};
+#[cfg(feature = "std")]
+pub use rollup::{Granularity, RollUp, RollUpBucket};
+pub use score::Score;
+pub use seasonality::Seasonality;
+#[cfg(feature = "std")]
+pub use sharded::ShardedMoving;
+#[cfg(feature = "std")]
+pub use shared::SharedMoving;
+pub use snapshot::MovingSnapshot;
+pub use state::MovingState;
+pub use stratified::Stratified;
+pub use theil_sen::TheilSen;
+pub use threshold::{Statistic, Threshold, ThresholdBreach};
+#[cfg(feature = "std")]
+pub use time_windowed::TimeWindowedMoving;
+pub use trend::Trend;
+pub use vector::MovingVec;
+pub use weighted_window::WeightedWindow;
+pub use windowed::WindowedMoving;
+
+#[cfg(feature = "derive")]
+pub use moving_average_derive::MovingStats;
 
 macro_rules! from_size {
     ($($ty:ty),*) => {
         $(
+            #[allow(deprecated)]
             impl FromUsize for $ty {
                 fn from_usize(value: usize) -> Self {
                     value as Self
                 }
             }
 
+            #[allow(deprecated)]
             impl ToFloat64 for $ty {
                 fn to_f64(self) -> f64 {
                     self as f64
                 }
             }
-        )*
-    };
-}
 
-macro_rules! assign_types {
-    ($($ty:ty),*) => {
-        $(
-            impl AddAssign<$ty> for Moving<$ty> {
-                fn add_assign(&mut self, other: $ty) {
-                    self.add(other);
+            impl MovingInput for $ty {
+                fn to_f64(self) -> f64 {
+                    self as f64
                 }
             }
-
         )*
-
-
     };
 }
 
@@ -79,7 +337,7 @@ macro_rules! partials {
             }
 
             impl PartialOrd<$ty> for Moving<$ty> {
-                fn partial_cmp(&self, other: &$ty) -> Option<std::cmp::Ordering> {
+                fn partial_cmp(&self, other: &$ty) -> Option<core::cmp::Ordering> {
                     self.mean.partial_cmp(&(*other as f64))
                 }
             }
@@ -91,7 +349,7 @@ macro_rules! partials {
             }
 
             impl PartialOrd<Moving<$ty>> for $ty {
-                fn partial_cmp(&self, other: &Moving<$ty>) -> Option<std::cmp::Ordering> {
+                fn partial_cmp(&self, other: &Moving<$ty>) -> Option<core::cmp::Ordering> {
                     (*self as f64).partial_cmp(&other.mean)
                 }
             }
@@ -124,6 +382,7 @@ macro_rules! partial_non {
 macro_rules! signed {
     ($($ty:ty), *) => {
         $(
+        #[allow(deprecated)]
         impl Sign for $ty {
             fn is_unsigned() -> bool {
                 false
@@ -135,6 +394,7 @@ macro_rules! signed {
 macro_rules! unsigned {
     ($($ty:ty), *) => {
     $(
+        #[allow(deprecated)]
         impl Sign for $ty {
             fn is_unsigned() -> bool {
                 true
@@ -145,47 +405,1675 @@ macro_rules! unsigned {
 }
 
 from_size!(usize, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
-assign_types!(usize, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
 partials!(usize, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
 partial_non!(usize, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
 signed!(i8, i16, i32, i64, i128, f32, f64);
 unsigned!(usize, u8, u16, u32, u64, u128);
 
+/// Direction of a move between consecutive values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Rising,
+    Falling,
+}
+
+/// What to do with a missing sample passed to [`Moving::add_opt`] or
+/// [`Moving::add_result`]. Missing samples are counted under every policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingPolicy {
+    /// Leave the statistics untouched.
+    #[default]
+    Skip,
+    /// Add a zero in place of the missing sample.
+    Zero,
+    /// Leave the statistics untouched and return
+    /// [`MovingError::MissingValue`].
+    Error,
+}
+
+/// What to do with a NaN or infinite sample. Such samples are counted
+/// under every policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinitePolicy {
+    /// Record the sample, which makes the mean NaN or infinite.
+    #[default]
+    Accept,
+    /// Leave the statistics untouched.
+    Skip,
+    /// Leave the statistics untouched and return
+    /// [`MovingError::NonFinite`].
+    Reject,
+}
+
+/// What to do when the count of values would pass `usize::MAX`. The
+/// mean is kept from the sum of weights, so it stays correct under every
+/// policy that records the value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Leave the statistics untouched and return
+    /// [`MovingError::CountOverflow`].
+    #[default]
+    Error,
+    /// Record the value and keep the count at `usize::MAX`.
+    Saturate,
+    /// Start over: the statistics are [reset](Moving::reset) and the value
+    /// is recorded as the first of a new run.
+    Wrap,
+}
+
+/// Most samples [`Moving::with_gap_policy`] fills into one gap by default.
+const DEFAULT_MAX_GAP: u32 = 1024;
+
+/// How samples missing from a timestamped stream are filled in, by
+/// [`Moving::with_gap_policy`] and the `Resampler`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GapPolicy {
+    /// Leave the gap empty.
+    #[default]
+    Skip,
+    /// Fill the gap with zeros.
+    Zero,
+    /// Repeat the value before the gap.
+    CarryForward,
+    /// Fill the gap on the straight line between the values on either
+    /// side of it.
+    Interpolate,
+}
+
 #[derive(Debug, Default)]
 pub struct Moving<T> {
     count: usize,
+    weight: f64,
     mean: f64,
-    phantom: std::marker::PhantomData<T>,
+    compensated: Option<CompensatedSum>,
+    m2: f64,
+    log2_sum: f64,
+    reciprocal_sum: f64,
+    positive: usize,
+    first: Option<f64>,
+    first_at: Option<Instant>,
+    last: Option<f64>,
+    last_at: Option<Instant>,
+    min: Option<f64>,
+    min_at: Option<Instant>,
+    max: Option<f64>,
+    max_at: Option<Instant>,
+    arrivals: Option<(Instant, Instant, usize)>,
+    history: Option<Window>,
+    integral: f64,
+    held: f64,
+    held_seconds: f64,
+    jitter: f64,
+    jitter_count: usize,
+    crossing_reference: Option<f64>,
+    crossing_side: Option<bool>,
+    crossings: usize,
+    streak: Option<(Direction, usize)>,
+    longest_rising: usize,
+    longest_falling: usize,
+    missing_policy: MissingPolicy,
+    missing: usize,
+    gap: Option<(Duration, GapPolicy)>,
+    max_gap: u32,
+    non_finite_policy: NonFinitePolicy,
+    non_finite: usize,
+    overflow_policy: OverflowPolicy,
+    pipeline: Pipeline,
+    frozen: bool,
+    ignored: usize,
+    mode: Option<ModeTracker>,
+    median: Option<MedianTracker>,
+    quantiles: Vec<Quantile<f64>>,
+    threshold: Option<(Statistic, Threshold)>,
+    threshold_reached: bool,
+    observer: Option<Observer>,
+    phantom: core::marker::PhantomData<T>,
 }
 
+#[deprecated(note = "the accumulators only need `MovingValue` or `MovingInput`")]
 pub trait FromUsize {
     fn from_usize(value: usize) -> Self;
 }
 
+#[deprecated(note = "implement `MovingInput` instead")]
 pub trait ToFloat64 {
     fn to_f64(self) -> f64;
 }
 
+#[deprecated(note = "the accumulators only need `MovingValue` or `MovingInput`")]
 pub trait Sign {
     fn is_unsigned() -> bool;
 }
 
+/// Values that can be fed into the accumulators.
+///
+/// Implemented for the primitive numeric types; implement it for your own
+/// numeric wrappers (money, fixed-point, units) to ingest them directly,
+/// and [`FromStatistic`] as well to read statistics back as them, see
+/// [`MovingValue`].
+///
+/// ```rust
+/// use moving_average::{Moving, MovingInput};
+///
+/// struct Cents(i64);
+///
+/// impl MovingInput for Cents {
+///     fn to_f64(self) -> f64 {
+///         self.0 as f64 / 100.0
+///     }
+/// }
+///
+/// let mut moving_average: Moving<Cents> = Moving::new();
+/// moving_average.add(Cents(150));
+/// moving_average.add(Cents(250));
+/// assert_eq!(*moving_average, 2.0);
+/// ```
+pub trait MovingInput {
+    fn to_f64(self) -> f64;
+}
+
+/// Durations are averaged in seconds.
+impl MovingInput for Duration {
+    fn to_f64(self) -> f64 {
+        self.as_secs_f64()
+    }
+}
+
 impl<T> Moving<T>
 where
-    T: FromUsize + ToFloat64 + Sign,
+    T: MovingInput,
 {
     pub fn new() -> Self {
         Self {
             count: 0,
+            weight: 0.0,
             mean: 0.0,
-            phantom: std::marker::PhantomData,
+            compensated: None,
+            m2: 0.0,
+            log2_sum: 0.0,
+            reciprocal_sum: 0.0,
+            positive: 0,
+            first: None,
+            first_at: None,
+            last: None,
+            last_at: None,
+            min: None,
+            min_at: None,
+            max: None,
+            max_at: None,
+            arrivals: None,
+            history: None,
+            integral: 0.0,
+            held: 0.0,
+            held_seconds: 0.0,
+            jitter: 0.0,
+            jitter_count: 0,
+            crossing_reference: None,
+            crossing_side: None,
+            crossings: 0,
+            streak: None,
+            longest_rising: 0,
+            longest_falling: 0,
+            missing_policy: MissingPolicy::Skip,
+            missing: 0,
+            gap: None,
+            max_gap: DEFAULT_MAX_GAP,
+            non_finite_policy: NonFinitePolicy::Accept,
+            non_finite: 0,
+            overflow_policy: OverflowPolicy::Error,
+            pipeline: Pipeline::default(),
+            frozen: false,
+            ignored: 0,
+            mode: None,
+            median: None,
+            quantiles: Vec::new(),
+            threshold: None,
+            threshold_reached: false,
+            observer: None,
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Resumes from a previously computed mean over `count` values, e.g. a
+    /// checkpoint. Extremes and first/last values of the prior period are
+    /// not known and start empty; the variance treats the prior values as
+    /// if they all equalled `mean`. The prior values are not known to be
+    /// positive either, so the geometric and harmonic means stay `None`.
+    pub fn with_initial(mean: f64, count: usize) -> Self {
+        Self {
+            count,
+            weight: count as f64,
+            mean: if count == 0 { 0.0 } else { mean },
+            ..Self::new()
         }
     }
 
+    /// Reports [`MovingError::ThresholdReached`] from adds that leave the
+    /// mean past `threshold`, see [`with_threshold`](Self::with_threshold).
+    pub fn new_with_threshold(threshold: impl Into<Threshold>) -> Self {
+        Self::new().with_threshold(threshold)
+    }
+
+    /// Reports [`MovingError::ThresholdReached`] from every add or remove
+    /// that leaves the mean past `threshold`; the value is still recorded.
+    /// See [`with_threshold_on`](Self::with_threshold_on) to bound another
+    /// statistic.
+    ///
+    /// ```rust
+    /// use moving_average::{Moving, MovingError, Threshold};
+    ///
+    /// let mut throughput: Moving<u32> = Moving::new().with_threshold(Threshold::Lower(100.0));
+    /// assert_eq!(throughput.add_with_result(150).map(|outcome| outcome.mean), Ok(150.0));
+    /// assert!(matches!(throughput.add_with_result(10), Err(MovingError::ThresholdReached(_))));
+    ///
+    /// let mut temperature: Moving<f64> = Moving::new_with_threshold(18.0..24.0);
+    /// assert_eq!(temperature.add_with_result(21.0).map(|outcome| outcome.mean), Ok(21.0));
+    /// let Err(MovingError::ThresholdReached(breach)) = temperature.add_with_result(30.0) else {
+    ///     panic!("expected the range to be left");
+    /// };
+    /// assert_eq!((breach.mean, breach.bound, breach.value), (25.5, 24.0, 30.0));
+    /// ```
+    pub fn with_threshold(self, threshold: impl Into<Threshold>) -> Self {
+        self.with_threshold_on(Statistic::Mean, threshold)
+    }
+
+    /// Like [`with_threshold`](Self::with_threshold), bounding `statistic`
+    /// instead of the mean. A statistic that is not available yet, such as
+    /// the variance of a single value, does not reach any threshold.
+    ///
+    /// ```rust
+    /// use moving_average::{Moving, MovingError, Statistic};
+    ///
+    /// let mut samples: Moving<u32> = Moving::new().with_threshold_on(Statistic::Last, 500.0);
+    /// assert_eq!(samples.add_with_result(20).map(|outcome| outcome.mean), Ok(20.0));
+    /// assert!(matches!(samples.add_with_result(980), Err(MovingError::ThresholdReached(_))));
+    /// assert_eq!(samples.add_with_result(200).map(|outcome| outcome.mean), Ok(400.0));
+    /// ```
+    pub fn with_threshold_on(
+        mut self,
+        statistic: Statistic,
+        threshold: impl Into<Threshold>,
+    ) -> Self {
+        self.threshold = Some((statistic, threshold.into()));
+        self
+    }
+
+    pub fn threshold(&self) -> Option<&Threshold> {
+        self.threshold.as_ref().map(|(_, threshold)| threshold)
+    }
+
+    /// The statistic the threshold bounds, if one is configured.
+    pub fn threshold_statistic(&self) -> Option<Statistic> {
+        self.threshold.as_ref().map(|(statistic, _)| *statistic)
+    }
+
+    /// Derives the mean from a compensated (Neumaier) sum of the values,
+    /// so rounding error does not build up over billions of adds of values
+    /// with a large dynamic range.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut moving_average: Moving<f64> = Moving::new().with_compensated_mean();
+    /// moving_average.add_slice(&[1.0, 1e100, 1.0, -1e100]);
+    /// assert_eq!(moving_average, 0.5);
+    /// ```
+    pub fn with_compensated_mean(mut self) -> Self {
+        let mut sum = CompensatedSum::default();
+        sum.add(self.mean * self.weight);
+        self.compensated = Some(sum);
+        self
+    }
+
+    /// Keeps the mean as it stood after each of the last `depth` adds, for
+    /// [`percent_change`](Self::percent_change).
+    pub fn with_history(mut self, depth: usize) -> Self {
+        self.history = Some(Window::new(depth.saturating_add(1)));
+        self
+    }
+
+    /// Counts [`crossings`](Self::crossings) of a fixed `reference` instead
+    /// of the running mean.
+    pub fn with_crossing_reference(mut self, reference: f64) -> Self {
+        self.crossing_reference = Some(reference);
+        self
+    }
+
+    pub fn with_missing_policy(mut self, policy: MissingPolicy) -> Self {
+        self.missing_policy = policy;
+        self
+    }
+
+    /// Sets what happens to NaN and infinite samples, which are otherwise
+    /// recorded and carry over into the mean.
+    ///
+    /// ```rust
+    /// use moving_average::{Moving, MovingError, NonFinitePolicy};
+    ///
+    /// let mut moving: Moving<f64> = Moving::new().with_non_finite_policy(NonFinitePolicy::Reject);
+    /// moving.add(2.0);
+    /// assert_eq!(moving.add_with_result(f64::NAN), Err(MovingError::NonFinite));
+    /// moving.add(f64::INFINITY);
+    /// assert_eq!(moving.mean(), 2.0);
+    /// assert_eq!(moving.non_finite(), 2);
+    /// ```
+    pub fn with_non_finite_policy(mut self, policy: NonFinitePolicy) -> Self {
+        self.non_finite_policy = policy;
+        self
+    }
+
+    /// Sets what happens once the count of values reaches `usize::MAX`.
+    ///
+    /// ```rust
+    /// use moving_average::{Moving, MovingError, OverflowPolicy};
+    ///
+    /// let mut strict: Moving<u8> = Moving::from_parts(usize::MAX, 4.0, None, None);
+    /// assert_eq!(strict.add_with_result(8), Err(MovingError::CountOverflow));
+    ///
+    /// let mut wrapping: Moving<u8> =
+    ///     Moving::from_parts(usize::MAX, 4.0, None, None).with_overflow_policy(OverflowPolicy::Wrap);
+    /// wrapping.add(8);
+    /// assert_eq!(wrapping.count(), 1);
+    /// assert_eq!(wrapping.mean(), 8.0);
+    /// ```
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow_policy = policy;
+        self
+    }
+
+    /// Expects a sample every `interval` from [`add_at`](Self::add_at) and
+    /// fills in the samples missing from longer gaps per `policy`. Filled
+    /// samples are counted as [`missing`](Self::missing). Gaps of more than
+    /// [`with_max_gap`](Self::with_max_gap) samples are left empty.
+    ///
+    /// ```rust
+    /// use moving_average::{GapPolicy, Moving};
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let start = Instant::now();
+    /// let second = Duration::from_secs(1);
+    /// let mut temperature: Moving<f64> =
+    ///     Moving::new().with_gap_policy(second, GapPolicy::Interpolate);
+    /// temperature.add_at(10.0, start);
+    /// temperature.add_at(40.0, start + second * 3);
+    /// assert_eq!(temperature.count(), 4);
+    /// assert_eq!(temperature.missing(), 2);
+    /// assert_eq!(temperature.mean(), 25.0);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn with_gap_policy(mut self, interval: Duration, policy: GapPolicy) -> Self {
+        self.gap = (policy != GapPolicy::Skip && !interval.is_zero()).then_some((interval, policy));
+        self
+    }
+
+    /// Fills at most `samples` missing samples into one gap, 1024 by
+    /// default; longer gaps, such as an outage or a clock jump, are left
+    /// empty.
+    #[cfg(feature = "std")]
+    pub fn with_max_gap(mut self, samples: u32) -> Self {
+        self.max_gap = samples;
+        self
+    }
+
+    /// Counts value frequencies so [`mode`](Self::mode) is available.
+    pub fn with_mode_tracking(mut self) -> Self {
+        self.mode.get_or_insert_with(ModeTracker::default);
+        self
+    }
+
+    /// Counts value frequencies with `backend`, enabling mode tracking.
+    /// Binned and tolerance backends with a width that is not positive and
+    /// finite count exact values instead.
+    ///
+    /// ```rust
+    /// use moving_average::{ModeBackend, Moving};
+    ///
+    /// let mut ids: Moving<u64> =
+    ///     Moving::new().with_mode_backend(ModeBackend::SpaceSaving { capacity: 16 });
+    /// for id in 0..10_000 {
+    ///     ids.add(id % 1000);
+    ///     ids.add(42);
+    /// }
+    /// assert_eq!(ids.mode(), Some(42.0));
+    /// ```
+    pub fn with_mode_backend(mut self, backend: ModeBackend) -> Self {
+        self.mode
+            .get_or_insert_with(ModeTracker::default)
+            .set_backend(backend);
+        self
+    }
+
+    /// Counts values within `epsilon / 2` of the same multiple of `epsilon`
+    /// together, so noisy readings still have a meaningful
+    /// [`mode`](Self::mode).
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut temperature: Moving<f64> = Moving::new().with_mode_tolerance(0.5);
+    /// for reading in [21.9, 22.1, 22.2, 23.4, 21.8] {
+    ///     temperature.add(reading);
+    /// }
+    /// assert_eq!(temperature.mode(), Some(22.0));
+    /// ```
+    pub fn with_mode_tolerance(self, epsilon: f64) -> Self {
+        self.with_mode_backend(ModeBackend::Tolerance { epsilon })
+    }
+
+    /// Keeps every value so the exact [`median`](Self::median) is available.
+    pub fn with_median(mut self) -> Self {
+        self.median.get_or_insert_with(MedianTracker::default);
+        self
+    }
+
+    /// Estimates the `q` quantile, see [`quantile`](Self::quantile); call
+    /// once per quantile of interest.
+    pub fn with_quantile(mut self, q: f64) -> Self {
+        self.quantiles.push(Quantile::new(q));
+        self
+    }
+
+    /// Caps the memory used for mode tracking at roughly `bytes`, enabling
+    /// it if needed. Once distinct values would exceed the budget the exact
+    /// counts are folded into bins, see [`mode_backend`](Self::mode_backend).
+    pub fn with_memory_budget(mut self, bytes: usize) -> Self {
+        self.mode
+            .get_or_insert_with(ModeTracker::default)
+            .set_budget(bytes);
+        self
+    }
+
+    /// Rejects values outside `min..=max` as physically implausible.
+    ///
+    /// This and the other `with_*` ingestion stages run in the order they
+    /// are configured; dropped values are counted per stage and leave the
+    /// statistics untouched.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut latency: Moving<f64> = Moving::new()
+    ///     .with_valid_range(0.0, 60.0)
+    ///     .with_filter(|seconds| seconds != 30.0)
+    ///     .with_transform(|seconds| seconds * 1000.0);
+    /// latency.add(0.5);
+    /// latency.add(-1.0);
+    /// latency.add(30.0);
+    /// assert_eq!(latency, 500.0);
+    /// assert_eq!(latency.rejected(), 2);
+    /// ```
+    pub fn with_valid_range(mut self, min: f64, max: f64) -> Self {
+        self.pipeline.validate(min, max);
+        self
+    }
+
+    /// Drops values for which `filter` returns `false`.
+    pub fn with_filter(mut self, filter: impl Fn(f64) -> bool + Send + Sync + 'static) -> Self {
+        self.pipeline.filter(Filter::new(filter));
+        self
+    }
+
+    /// Drops values too far from the values accumulated so far, so sensor
+    /// glitches do not skew the statistics; see
+    /// [`stages`](Self::stages) for how many were dropped. Values are kept
+    /// until there are enough to judge: two with some spread for
+    /// [`OutlierRule::StdDev`], and a non-zero median absolute deviation
+    /// for [`OutlierRule::Mad`], which turns on
+    /// [`with_median`](Self::with_median). Since dropped values are never
+    /// accumulated, a lasting jump in level keeps being rejected.
+    ///
+    /// ```rust
+    /// use moving_average::{Moving, OutlierRule};
+    ///
+    /// let mut temperature: Moving<f64> =
+    ///     Moving::new().with_outlier_rejection(OutlierRule::StdDev(3.0));
+    /// for reading in [20.0, 21.0, 19.0, 20.0, 85.0, 21.0] {
+    ///     temperature.add(reading);
+    /// }
+    /// assert_eq!(temperature.count(), 5);
+    /// assert_eq!(temperature.rejected(), 1);
+    /// ```
+    pub fn with_outlier_rejection(mut self, rule: OutlierRule) -> Self {
+        self.pipeline.outliers(rule);
+        if matches!(rule, OutlierRule::Mad(_)) {
+            self = self.with_median();
+        }
+        self
+    }
+
+    /// Rewrites each value reaching this stage, e.g. a unit conversion or
+    /// clamping.
+    pub fn with_transform(
+        mut self,
+        transform: impl Fn(f64) -> f64 + Send + Sync + 'static,
+    ) -> Self {
+        self.pipeline.transform(Transform::new(transform));
+        self
+    }
+
+    /// Adds a value; values rejected by the configuration are dropped, see
+    /// [`add_with_result`](Self::add_with_result) to observe why.
     pub fn add(&mut self, value: T) {
+        let _ = self.ingest(T::to_f64(value), 1.0, None);
+    }
+
+    /// Adds a value, returning what it changed, why the value was
+    /// rejected, or that the mean reached the configured threshold.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut moving: Moving<u32> = Moving::new().with_mode_tracking();
+    /// moving.add_slice(&[4, 6, 8]);
+    /// let outcome = moving.add_with_result(8).unwrap();
+    /// assert_eq!((outcome.count, outcome.mode_changed), (4, true));
+    /// assert_eq!(outcome.z_score, Some(1.0));
+    /// ```
+    pub fn add_with_result(&mut self, value: T) -> Result<AddOutcome, MovingError> {
+        let (mode, was_reached) = (self.mode(), self.threshold_reached);
+        let z_score = match self.admit(T::to_f64(value))? {
+            Some(value) => {
+                let z_score = self.z_score(value);
+                self.record(value, 1.0, None);
+                self.check_threshold(value)?;
+                z_score
+            }
+            None => None,
+        };
+        Ok(AddOutcome {
+            mean: self.mean,
+            count: self.count,
+            mode_changed: self.mode() != mode,
+            z_score,
+            threshold_cleared: was_reached && !self.threshold_reached,
+        })
+    }
+
+    /// Adds a value carrying `weight`, e.g. a trade's volume or a request's
+    /// size, so the mean becomes a weighted mean. [`add`](Self::add) gives
+    /// each value a weight of one.
+    ///
+    /// The variance is weighted the same way, and the mode is the value with
+    /// the largest accumulated weight. The median, quantile estimates and a
+    /// count-min mode backend count each value once, so a weight other than
+    /// one is refused with [`MovingError::UnweightedStatistic`] while any of
+    /// them is enabled.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut vwap: Moving<f64> = Moving::new();
+    /// vwap.add_weighted(100.0, 300.0).unwrap();
+    /// vwap.add_weighted(102.0, 100.0).unwrap();
+    /// assert_eq!(vwap, 100.5);
+    /// assert_eq!(vwap.total_weight(), 400.0);
+    /// ```
+    pub fn add_weighted(&mut self, value: T, weight: f64) -> Result<f64, MovingError> {
+        if !(weight.is_finite() && weight > 0.0) {
+            return Err(MovingError::InvalidWeight);
+        }
+        if weight != 1.0 && !self.takes_weights() {
+            return Err(MovingError::UnweightedStatistic);
+        }
+        self.ingest(T::to_f64(value), weight, None)?;
+        Ok(self.mean)
+    }
+
+    /// Whether every enabled statistic can take a sample's weight into
+    /// account.
+    fn takes_weights(&self) -> bool {
+        #[cfg(feature = "count-min")]
+        if matches!(self.mode_backend(), Some(ModeBackend::CountMin { .. })) {
+            return false;
+        }
+        self.median.is_none() && self.quantiles.is_empty()
+    }
+
+    /// Adds every value of a batch, as [`add`](Self::add) would one by one.
+    pub fn add_iter(&mut self, values: impl IntoIterator<Item = T>) {
+        for value in values {
+            let _ = self.ingest(T::to_f64(value), 1.0, None);
+        }
+    }
+
+    /// Adds every value of a slice, see [`add_iter`](Self::add_iter).
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut moving_average: Moving<u16> = Moving::new();
+    /// moving_average.add_slice(&[1, 2, 3]);
+    /// moving_average.add_iter(vec![4, 5]);
+    /// assert_eq!(moving_average, 3);
+    /// ```
+    pub fn add_slice(&mut self, values: &[T])
+    where
+        T: Copy,
+    {
+        self.add_iter(values.iter().copied());
+    }
+
+    /// Adds the value if present, otherwise applies the missing policy.
+    pub fn add_opt(&mut self, value: Option<T>) -> Result<(), MovingError> {
+        match value {
+            Some(value) => self.add_with_result(value).map(|_| ()),
+            None => self.add_missing(),
+        }
+    }
+
+    /// Adds the value if `Ok`, treating an `Err` as a missing sample.
+    pub fn add_result<E>(&mut self, value: Result<T, E>) -> Result<(), MovingError> {
+        self.add_opt(value.ok())
+    }
+
+    fn add_missing(&mut self) -> Result<(), MovingError> {
+        if self.frozen {
+            self.ignored += 1;
+            return Err(MovingError::Frozen);
+        }
+        self.missing += 1;
+        match self.missing_policy {
+            MissingPolicy::Skip => Ok(()),
+            MissingPolicy::Zero => self.ingest(0.0, 1.0, None),
+            MissingPolicy::Error => Err(MovingError::MissingValue),
+        }
+    }
+
+    /// Adds a value observed at `at`, which is kept alongside the value.
+    #[cfg(feature = "std")]
+    pub fn add_at(&mut self, value: T, at: Instant) {
+        let value = T::to_f64(value);
+        self.fill_gap(value, at);
+        let _ = self.ingest(value, 1.0, Some(at));
+    }
+
+    /// Adds the samples the gap policy puts between the last value and
+    /// `value`, observed at `at`.
+    #[cfg(feature = "std")]
+    fn fill_gap(&mut self, value: f64, at: Instant) {
+        let (Some((interval, policy)), Some(last), Some(last_at), false) =
+            (self.gap, self.last, self.last_at, self.frozen)
+        else {
+            return;
+        };
+        let steps = compat::round(
+            at.saturating_duration_since(last_at).as_secs_f64() / interval.as_secs_f64(),
+        );
+        let missed = steps - 1.0;
+        if !(1.0..=f64::from(self.max_gap)).contains(&missed) {
+            return;
+        }
+        // A whole number within the limit, so it converts exactly.
+        for step in 1..=missed as u32 {
+            let filled = match policy {
+                GapPolicy::Skip => return,
+                GapPolicy::Zero => 0.0,
+                GapPolicy::CarryForward => last,
+                GapPolicy::Interpolate => last + (value - last) * f64::from(step) / steps,
+            };
+            self.missing += 1;
+            let _ = self.ingest(filled, 1.0, Some(last_at + interval * step));
+        }
+    }
+
+    pub(crate) fn add_f64(&mut self, value: f64) {
+        let _ = self.ingest(value, 1.0, None);
+    }
+
+    fn ingest(&mut self, value: f64, weight: f64, at: Option<Instant>) -> Result<(), MovingError> {
+        let Some(value) = self.admit(value)? else {
+            return Ok(());
+        };
+        self.record(value, weight, at);
+        self.check_threshold(value)
+    }
+
+    /// Runs the checks and ingestion stages ahead of recording `value`;
+    /// returns the value to record, or `None` when it is skipped.
+    fn admit(&mut self, value: f64) -> Result<Option<f64>, MovingError> {
+        if self.frozen {
+            self.ignored += 1;
+            return Err(MovingError::Frozen);
+        }
+        if !value.is_finite() {
+            self.non_finite += 1;
+            match self.non_finite_policy {
+                NonFinitePolicy::Accept => {}
+                NonFinitePolicy::Skip => return Ok(None),
+                NonFinitePolicy::Reject => {
+                    let reason = MovingError::NonFinite;
+                    self.notify(MovingEvent::Rejected { value, reason });
+                    return Err(reason);
+                }
+            }
+        }
+        let mut pipeline = core::mem::take(&mut self.pipeline);
+        let result = pipeline.run(value, |rule| self.spread(rule));
+        self.pipeline = pipeline;
+        if let Err(reason) = result {
+            self.notify(MovingEvent::Rejected { value, reason });
+        }
+        let value = result?;
+        if self.count == usize::MAX && self.overflow_policy == OverflowPolicy::Error {
+            return Err(MovingError::CountOverflow);
+        }
+        Ok(Some(value))
+    }
+
+    /// Centre and non-zero scale an outlier rule measures against.
+    fn spread(&self, rule: OutlierRule) -> Option<(f64, f64)> {
+        let (centre, scale) = match rule {
+            OutlierRule::StdDev(_) => (self.mean, self.std_dev()?),
+            OutlierRule::Mad(_) => (self.median()?, self.median_absolute_deviation()?),
+        };
+        (scale > 0.0).then_some((centre, scale))
+    }
+
+    /// Updates the threshold state after `value` was added or removed.
+    fn check_threshold(&mut self, value: f64) -> Result<(), MovingError> {
+        let Some((statistic, threshold)) = &self.threshold else {
+            return Ok(());
+        };
+        let observed = match statistic {
+            Statistic::Mean => Some(self.mean),
+            Statistic::Count => Some(self.count as f64),
+            Statistic::Mode => self.mode(),
+            Statistic::Variance => self.variance(),
+            Statistic::Last => self.last,
+        };
+        let was_reached = self.threshold_reached;
+        self.threshold_reached = match observed {
+            Some(observed) => threshold.update(observed, was_reached),
+            None => false,
+        };
+        let breach = ThresholdBreach {
+            statistic: *statistic,
+            observed: observed.unwrap_or(f64::NAN),
+            bound: observed.map_or(f64::NAN, |observed| threshold.bound(observed)),
+            mean: self.mean,
+            value,
+        };
+        if self.observer.is_some() && self.threshold_reached != was_reached {
+            let snapshot = self.snapshot();
+            self.notify(if self.threshold_reached {
+                MovingEvent::ThresholdReached(snapshot)
+            } else {
+                MovingEvent::ThresholdCleared(snapshot)
+            });
+        }
+        if self.threshold_reached {
+            Err(MovingError::ThresholdReached(breach))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Whether the most recent add or remove left the threshold reached.
+    pub fn is_threshold_reached(&self) -> bool {
+        self.threshold_reached
+    }
+
+    fn record(&mut self, value: f64, weight: f64, at: Option<Instant>) {
+        if self.count == usize::MAX && self.overflow_policy == OverflowPolicy::Wrap {
+            self.reset();
+        }
+        let reference = match self.crossing_reference {
+            Some(reference) => Some(reference),
+            None => (self.count > 0).then_some(self.mean),
+        };
+        if let Some(reference) = reference.filter(|reference| value != *reference) {
+            let above = value > reference;
+            if self.crossing_side.is_some_and(|side| side != above) {
+                self.crossings += 1;
+            }
+            self.crossing_side = Some(above);
+        }
+
+        self.count = self.count.saturating_add(1);
+        self.weight += weight;
+        let delta = value - self.mean;
+        self.mean += delta * weight / self.weight;
+        self.m2 += weight * delta * (value - self.mean);
+        if let Some(sum) = &mut self.compensated {
+            sum.add(value * weight);
+            self.mean = sum.value() / self.weight;
+        }
+        if value > 0.0 {
+            self.log2_sum += weight * compat::log2(value);
+            self.reciprocal_sum += weight / value;
+            self.positive = self.positive.saturating_add(1);
+        }
+        if self.first.is_none() {
+            self.first = Some(value);
+            self.first_at = at;
+        }
+        if let Some(previous) = self.last {
+            self.update_streak(value, previous);
+            self.jitter_count += 1;
+            self.jitter += ((value - previous).abs() - self.jitter) / self.jitter_count as f64;
+        }
+        if let (Some(previous), Some(previous_at), Some(at)) = (self.last, self.last_at, at) {
+            let elapsed = at.saturating_duration_since(previous_at).as_secs_f64();
+            self.integral += (previous + value) / 2.0 * elapsed;
+            self.held += previous * elapsed;
+            self.held_seconds += elapsed;
+        }
+        self.last = Some(value);
+        self.last_at = at;
+        #[cfg(feature = "std")]
+        if let Some(at) = at {
+            self.arrivals = Some(match self.arrivals {
+                Some((first, last, count)) => {
+                    (first.min(at), last.max(at), count.saturating_add(1))
+                }
+                None => (at, at, 1),
+            });
+        }
+        if self.min.is_none_or(|min| value < min) {
+            self.min = Some(value);
+            self.min_at = at;
+        }
+        if self.max.is_none_or(|max| value > max) {
+            self.max = Some(value);
+            self.max_at = at;
+        }
+        if let Some(history) = &mut self.history {
+            history.push(self.mean);
+        }
+        if let Some(mode) = &mut self.mode {
+            mode.add(value, weight, self.mean);
+        }
+        if let Some(median) = &mut self.median {
+            median.add(value);
+        }
+        for quantile in &mut self.quantiles {
+            quantile.add_f64(value);
+        }
+    }
+
+    fn update_streak(&mut self, value: f64, previous: f64) {
+        let direction = if value > previous {
+            Direction::Rising
+        } else if value < previous {
+            Direction::Falling
+        } else {
+            self.streak = None;
+            return;
+        };
+        let length = match self.streak {
+            Some((current, length)) if current == direction => length + 1,
+            _ => 1,
+        };
+        self.streak = Some((direction, length));
+        let longest = match direction {
+            Direction::Rising => &mut self.longest_rising,
+            Direction::Falling => &mut self.longest_falling,
+        };
+        *longest = (*longest).max(length);
+    }
+
+    /// Removes a previously added value, see
+    /// [`remove_with_result`](Self::remove_with_result).
+    pub fn remove(&mut self, value: T) {
+        let _ = self.remove_with_result(value);
+    }
+
+    /// Removes a previously added value from the mean, variance, mode and
+    /// median, returning the new mean.
+    ///
+    /// The value is taken as it was recorded, after any transform stage,
+    /// and removed with a weight of one. Extremes, quantile estimates,
+    /// first/last values and the other path-dependent statistics are left
+    /// as they were.
+    pub fn remove_with_result(&mut self, value: T) -> Result<f64, MovingError> {
+        if self.frozen {
+            self.ignored += 1;
+            return Err(MovingError::Frozen);
+        }
+        if self.count == 0 {
+            return Err(MovingError::Empty);
+        }
         let value = T::to_f64(value);
-        self.count += 1;
-        self.mean += (value - self.mean) / self.count as f64;
+        self.count -= 1;
+        self.weight -= 1.0;
+        if self.count == 0 || self.weight <= 0.0 {
+            self.weight = 0.0;
+            self.mean = 0.0;
+            self.m2 = 0.0;
+            self.compensated = self.compensated.map(|_| CompensatedSum::default());
+            self.log2_sum = 0.0;
+            self.reciprocal_sum = 0.0;
+            self.positive = 0;
+            self.arrivals = None;
+        } else {
+            let previous = self.mean;
+            self.mean -= (value - self.mean) / self.weight;
+            self.m2 = (self.m2 - (value - self.mean) * (value - previous)).max(0.0);
+            if let Some(sum) = &mut self.compensated {
+                sum.add(-value);
+                self.mean = sum.value() / self.weight;
+            }
+            if value > 0.0 {
+                self.log2_sum -= compat::log2(value);
+                self.reciprocal_sum -= 1.0 / value;
+                self.positive = self.positive.saturating_sub(1);
+            }
+        }
+        if let Some(mode) = &mut self.mode {
+            mode.remove(value, self.mean);
+        }
+        if let Some(median) = &mut self.median {
+            median.remove(value);
+        }
+        self.check_threshold(value)?;
+        Ok(self.mean)
+    }
+
+    /// Folds in the values summarized by `other`, e.g. to combine
+    /// per-worker averages into a global one.
+    ///
+    /// The count, mean, variance and extremes are combined exactly, as are
+    /// the mode and median when both sides track them. Statistics that
+    /// depend on the order of values, such as first/last values, streaks,
+    /// crossings and quantile estimates, are left as they were.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut global: Moving<u32> = Moving::new();
+    /// global.add(10);
+    /// let mut worker: Moving<u32> = Moving::new();
+    /// worker.add(20);
+    /// worker.add(30);
+    ///
+    /// global.merge(&worker);
+    /// assert_eq!(global.count(), 3);
+    /// assert_eq!(global, 20);
+    /// ```
+    pub fn merge(&mut self, other: &Moving<T>) {
+        if other.count == 0 {
+            return;
+        }
+        if self.overflow_policy == OverflowPolicy::Wrap
+            && self.count.checked_add(other.count).is_none()
+        {
+            self.reset();
+        }
+        let weight = self.weight + other.weight;
+        let delta = other.mean - self.mean;
+        self.mean += delta * other.weight / weight;
+        self.m2 += other.m2 + delta * delta * self.weight * other.weight / weight;
+        self.weight = weight;
+        self.count = self.count.saturating_add(other.count);
+        self.log2_sum += other.log2_sum;
+        self.reciprocal_sum += other.reciprocal_sum;
+        self.positive = self.positive.saturating_add(other.positive);
+        #[cfg(feature = "std")]
+        {
+            self.arrivals = match (self.arrivals, other.arrivals) {
+                (Some((first, last, count)), Some((other_first, other_last, other_count))) => {
+                    Some((
+                        first.min(other_first),
+                        last.max(other_last),
+                        count.saturating_add(other_count),
+                    ))
+                }
+                (own, other) => own.or(other),
+            };
+        }
+        if let Some(sum) = &mut self.compensated {
+            sum.add(
+                other
+                    .compensated
+                    .map_or(other.mean * other.weight, |other| other.value()),
+            );
+            self.mean = sum.value() / self.weight;
+        }
+        if let Some(min) = other
+            .min
+            .filter(|min| self.min.is_none_or(|own| min < &own))
+        {
+            self.min = Some(min);
+            self.min_at = other.min_at;
+        }
+        if let Some(max) = other
+            .max
+            .filter(|max| self.max.is_none_or(|own| max > &own))
+        {
+            self.max = Some(max);
+            self.max_at = other.max_at;
+        }
+        if let (Some(mode), Some(other)) = (&mut self.mode, &other.mode) {
+            mode.merge(other, self.mean);
+        }
+        if let (Some(median), Some(other)) = (&mut self.median, &other.median) {
+            median.merge(other);
+        }
+    }
+
+    /// Ignores every add until [`unfreeze`](Self::unfreeze), e.g. during a
+    /// maintenance window. Ignored adds are counted by
+    /// [`ignored`](Self::ignored).
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    pub fn unfreeze(&mut self) {
+        self.frozen = false;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Clears every statistic, starting a new measurement period.
+    pub fn reset(&mut self) {
+        let snapshot = self.observer.as_ref().map(|_| self.snapshot());
+        self.clear_statistics();
+        if let Some(snapshot) = snapshot {
+            self.notify(MovingEvent::Reset(snapshot));
+        }
+    }
+
+    /// Clears the statistics like [`reset`](Self::reset) without telling
+    /// the observer, for restoring a snapshot.
+    pub(crate) fn clear_statistics(&mut self) {
+        let history = self
+            .history
+            .take()
+            .map(|history| Window::new(history.capacity()));
+        let mut pipeline = core::mem::take(&mut self.pipeline);
+        pipeline.reset_counts();
+        *self = Self {
+            history,
+            crossing_reference: self.crossing_reference,
+            missing_policy: self.missing_policy,
+            gap: self.gap,
+            max_gap: self.max_gap,
+            non_finite_policy: self.non_finite_policy,
+            overflow_policy: self.overflow_policy,
+            pipeline,
+            frozen: self.frozen,
+            mode: self.mode.as_ref().map(ModeTracker::cleared),
+            median: self.median.as_ref().map(|_| MedianTracker::default()),
+            quantiles: self
+                .quantiles
+                .iter()
+                .map(|quantile| Quantile::new(quantile.q()))
+                .collect(),
+            threshold: self.threshold.take(),
+            compensated: self.compensated.map(|_| CompensatedSum::default()),
+            observer: self.observer.take(),
+            ..Self::new()
+        };
+    }
+
+    /// Calls `observer` on notable transitions, such as the threshold
+    /// becoming reached, with the statistics at that point, e.g. to emit
+    /// `tracing` events or log lines.
+    ///
+    /// ```rust
+    /// use moving_average::{Moving, MovingEvent, Threshold};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let events = Arc::new(Mutex::new(Vec::new()));
+    /// let log = Arc::clone(&events);
+    /// let mut cpu: Moving<f64> = Moving::new()
+    ///     .with_threshold(Threshold::Upper(80.0))
+    ///     .with_observer(move |event| log.lock().unwrap().push(*event));
+    /// cpu.add(70.0);
+    /// cpu.add(95.0);
+    /// cpu.add(99.0);
+    ///
+    /// let events = events.lock().unwrap();
+    /// assert_eq!(events.len(), 1);
+    /// let MovingEvent::ThresholdReached(snapshot) = events[0] else {
+    ///     panic!("expected the threshold to be reached");
+    /// };
+    /// assert_eq!(snapshot.mean, 82.5);
+    /// ```
+    pub fn with_observer(
+        mut self,
+        observer: impl Fn(&MovingEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.observer = Some(Observer::new(observer));
+        self
+    }
+
+    pub(crate) fn notify(&self, event: MovingEvent) {
+        if let Some(observer) = &self.observer {
+            observer.notify(event);
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Sum of the weights of the values added; equal to the count unless
+    /// values were added with [`add_weighted`](Self::add_weighted).
+    pub fn total_weight(&self) -> f64 {
+        self.weight
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Geometric mean, the right average for growth factors and ratios;
+    /// `None` when empty or once a value that is not positive was added.
+    /// Also `None` after resuming with [`with_initial`](Self::with_initial)
+    /// or [`from_parts`](Self::from_parts), which only know the plain mean;
+    /// [`with_state`](Self::with_state) restores it.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut growth: Moving<f64> = Moving::new();
+    /// growth.add(2.0);
+    /// growth.add(8.0);
+    /// assert_eq!(growth.geometric_mean(), Some(4.0));
+    /// ```
+    pub fn geometric_mean(&self) -> Option<f64> {
+        (self.count > 0 && self.positive == self.count)
+            .then(|| compat::exp2(self.log2_sum / self.weight))
+    }
+
+    /// Harmonic mean, the right average for rates such as speeds; `None`
+    /// when empty or once a value that is not positive was added. Like the
+    /// [`geometric_mean`](Self::geometric_mean), it needs the sums kept in a
+    /// full [`MovingState`] to survive a restore.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// // Two equal distances at 40 and 60 km/h.
+    /// let mut speed: Moving<f64> = Moving::new();
+    /// speed.add(40.0);
+    /// speed.add(60.0);
+    /// assert!((speed.harmonic_mean().unwrap() - 48.0).abs() < 1e-9);
+    /// ```
+    pub fn harmonic_mean(&self) -> Option<f64> {
+        (self.count > 0 && self.positive == self.count).then(|| self.weight / self.reciprocal_sum)
+    }
+
+    /// Most frequent value, when mode tracking is enabled; with
+    /// [`add_weighted`](Self::add_weighted), the value with the largest
+    /// accumulated weight. Ties go to the value added more often, then to
+    /// the value closest to the mean when the tie formed.
+    pub fn mode(&self) -> Option<f64> {
+        self.mode.as_ref()?.mode()
+    }
+
+    /// Median of the distances between each value and the
+    /// [`median`](Self::median), a spread measure robust to outliers; needs
+    /// [`with_median`](Self::with_median).
+    pub fn median_absolute_deviation(&self) -> Option<f64> {
+        self.median.as_ref()?.median_absolute_deviation()
+    }
+
+    /// Mean after dropping `fraction` of the values, rounded down, from each
+    /// end, e.g. `0.05` for the top and bottom 5%; `fraction` is clamped to
+    /// `0.0..=0.5`. Exact, so it needs the values kept by
+    /// [`with_median`](Self::with_median); `None` without them or when no
+    /// values would be left. [`WindowedMoving::trimmed_mean`] does the same
+    /// over a window.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut readings: Moving<f64> = Moving::new().with_median();
+    /// for reading in [19.0, 21.0, -999.0, 20.0, 9999.0] {
+    ///     readings.add(reading);
+    /// }
+    /// assert_eq!(readings.trimmed_mean(0.2), Some(20.0));
+    /// ```
+    pub fn trimmed_mean(&self, fraction: f64) -> Option<f64> {
+        self.median.as_ref()?.trimmed_mean(fraction)
+    }
+
+    /// Every value tied for the highest frequency, in ascending order, with
+    /// that frequency; empty unless mode tracking is enabled and values
+    /// were added.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut rolls: Moving<u8> = Moving::new().with_mode_tracking();
+    /// for roll in [6, 2, 6, 4, 2] {
+    ///     rolls.add(roll);
+    /// }
+    /// assert_eq!(rolls.modes(), vec![(2.0, 2), (6.0, 2)]);
+    /// ```
+    pub fn modes(&self) -> Vec<(f64, usize)> {
+        self.mode.as_ref().map_or_else(Vec::new, ModeTracker::modes)
+    }
+
+    /// Every counted value with its frequency, in ascending order of value;
+    /// empty unless mode tracking is enabled. Binned and tolerance backends
+    /// report each bucket at its centre, and a count-min sketch only its
+    /// mode candidates.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut latency: Moving<f64> = Moving::new().with_mode_tolerance(10.0);
+    /// for millis in [12.0, 18.0, 31.0, 9.0] {
+    ///     latency.add(millis);
+    /// }
+    /// assert_eq!(latency.frequencies(), vec![(10.0, 2), (20.0, 1), (30.0, 1)]);
+    /// ```
+    pub fn frequencies(&self) -> Vec<(f64, usize)> {
+        let Some(mode) = &self.mode else {
+            return Vec::new();
+        };
+        let mut frequencies = mode.counts();
+        frequencies.sort_by(|a, b| a.0.total_cmp(&b.0));
+        frequencies
+    }
+
+    /// The `k` most frequent values with their frequencies, most frequent
+    /// first; equal frequencies are ordered by value. Values are reported as
+    /// in [`frequencies`](Self::frequencies).
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut status: Moving<u16> = Moving::new().with_mode_tracking();
+    /// for code in [200, 404, 200, 500, 200, 404] {
+    ///     status.add(code);
+    /// }
+    /// assert_eq!(status.top_k(2), vec![(200.0, 3), (404.0, 2)]);
+    /// ```
+    pub fn top_k(&self, k: usize) -> Vec<(f64, usize)> {
+        let mut frequencies = self.frequencies();
+        frequencies.sort_by_key(|(_, frequency)| core::cmp::Reverse(*frequency));
+        frequencies.truncate(k);
+        frequencies
+    }
+
+    /// Exact median of the values, when enabled with
+    /// [`with_median`](Self::with_median); even counts average the two
+    /// middle values.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut latency: Moving<u32> = Moving::new().with_median();
+    /// for millis in [12, 15, 11, 9_000, 14] {
+    ///     latency.add(millis);
+    /// }
+    /// assert_eq!(latency.median(), Some(14.0));
+    /// ```
+    pub fn median(&self) -> Option<f64> {
+        self.median.as_ref()?.median()
+    }
+
+    /// Estimated `q` quantile, when configured with
+    /// [`with_quantile`](Self::with_quantile).
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut latency: Moving<u32> = Moving::new().with_quantile(0.5).with_quantile(0.99);
+    /// for millis in 1..=100 {
+    ///     latency.add(millis);
+    /// }
+    /// assert!(latency.quantile(0.99).unwrap() > 95.0);
+    /// assert_eq!(latency.quantile(0.9), None);
+    /// ```
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        self.quantiles
+            .iter()
+            .find(|quantile| quantile.q() == q)?
+            .estimate()
+    }
+
+    /// How often `value` has been counted, when mode tracking is enabled.
+    /// Binned backends report the count of the value's bin; the
+    /// space-saving and count-min backends give estimates.
+    pub fn estimated_frequency(&self, value: T) -> Option<usize> {
+        Some(self.mode.as_ref()?.frequency(value.to_f64()))
+    }
+
+    /// How frequencies are currently counted, when mode tracking is enabled.
+    pub fn mode_backend(&self) -> Option<ModeBackend> {
+        self.mode.as_ref().map(ModeTracker::backend)
+    }
+
+    /// Whether the memory budget forced mode tracking off exact counts.
+    pub fn is_mode_degraded(&self) -> bool {
+        self.mode.as_ref().is_some_and(ModeTracker::is_degraded)
+    }
+
+    /// Number of NaN or infinite values added, whatever the
+    /// [`NonFinitePolicy`].
+    pub fn non_finite(&self) -> usize {
+        self.non_finite
+    }
+
+    /// Number of adds ignored while frozen.
+    pub fn ignored(&self) -> usize {
+        self.ignored
+    }
+
+    /// Number of values dropped by any ingestion stage.
+    pub fn rejected(&self) -> usize {
+        self.pipeline.dropped()
+    }
+
+    /// Values dropped by each ingestion stage, in the order configured.
+    pub fn stages(&self) -> Vec<StageReport> {
+        self.pipeline.reports()
+    }
+
+    /// Number of missing samples seen by [`add_opt`](Self::add_opt) and
+    /// [`add_result`](Self::add_result).
+    pub fn missing(&self) -> usize {
+        self.missing
+    }
+
+    /// Trapezoidal area under the values added with timestamps, in value
+    /// × seconds. Only consecutive timestamped adds contribute.
+    #[cfg(feature = "std")]
+    pub fn integral(&self) -> f64 {
+        self.integral
+    }
+
+    /// Mean of the values added with timestamps, each weighted by how long
+    /// it was current: until the next timestamped add. The most recent
+    /// value has not been current for any time yet, see
+    /// [`time_weighted_mean_at`](Self::time_weighted_mean_at).
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let start = Instant::now();
+    /// let mut gauge: Moving<u32> = Moving::new();
+    /// gauge.add_at(10, start);
+    /// gauge.add_at(40, start + Duration::from_secs(9));
+    /// gauge.add_at(0, start + Duration::from_secs(10));
+    /// assert_eq!(gauge.time_weighted_mean(), Some(13.0));
+    /// assert_eq!(gauge.time_weighted_mean_at(start + Duration::from_secs(20)), Some(6.5));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn time_weighted_mean(&self) -> Option<f64> {
+        (self.held_seconds > 0.0).then(|| self.held / self.held_seconds)
+    }
+
+    /// Like [`time_weighted_mean`](Self::time_weighted_mean), with the most
+    /// recent value held until `now`.
+    #[cfg(feature = "std")]
+    pub fn time_weighted_mean_at(&self, now: Instant) -> Option<f64> {
+        let (held, seconds) = match (self.last, self.last_at) {
+            (Some(last), Some(last_at)) => {
+                let elapsed = now.saturating_duration_since(last_at).as_secs_f64();
+                (self.held + last * elapsed, self.held_seconds + elapsed)
+            }
+            _ => (self.held, self.held_seconds),
+        };
+        (seconds > 0.0).then(|| held / seconds)
+    }
+
+    /// Mean absolute difference between consecutive values; needs at least
+    /// two values.
+    pub fn jitter(&self) -> Option<f64> {
+        (self.jitter_count > 0).then_some(self.jitter)
+    }
+
+    /// Number of times the values crossed the running mean, or the
+    /// reference set with
+    /// [`with_crossing_reference`](Self::with_crossing_reference). Values
+    /// equal to the reference do not cross it.
+    pub fn crossings(&self) -> usize {
+        self.crossings
+    }
+
+    /// Direction and length of the run of consecutive increases or decreases
+    /// ending at the most recent value; a repeated value ends the run.
+    pub fn current_streak(&self) -> Option<(Direction, usize)> {
+        self.streak
+    }
+
+    /// Length of the longest run of consecutive moves in `direction`.
+    pub fn longest_streak(&self, direction: Direction) -> usize {
+        match direction {
+            Direction::Rising => self.longest_rising,
+            Direction::Falling => self.longest_falling,
+        }
+    }
+
+    /// Percentage change of the mean over the last `n` adds, e.g. `12.0` when
+    /// it rose by 12%. Needs [`with_history`](Self::with_history) of at
+    /// least `n` and a non-zero mean `n` adds ago.
+    pub fn percent_change(&self, n: usize) -> Option<f64> {
+        let history = self.history.as_ref()?;
+        let now = history.len().checked_sub(1)?;
+        let then = history.get(now.checked_sub(n)?)?;
+        (then != 0.0).then(|| (self.mean - then) / then.abs() * 100.0)
+    }
+
+    /// Sample variance of the values, kept with Welford's algorithm; needs
+    /// at least two values. Weighted values scale the population variance
+    /// by the same `n / (n - 1)` correction.
+    pub fn variance(&self) -> Option<f64> {
+        (self.count > 1).then(|| {
+            let n = self.count as f64;
+            self.m2 / self.weight * n / (n - 1.0)
+        })
+    }
+
+    /// Sample standard deviation; needs at least two values.
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(compat::sqrt)
+    }
+
+    /// Same as [`variance`](Self::variance), named for when the choice of
+    /// estimator should be explicit.
+    pub fn sample_variance(&self) -> Option<f64> {
+        self.variance()
+    }
+
+    /// Population variance, dividing by the total weight rather than
+    /// `n - 1`: the variance of exactly the values seen, not an estimate
+    /// for a larger population. Needs at least one value.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut dice: Moving<u8> = Moving::new();
+    /// for face in [2, 4, 4, 4, 5, 5, 7, 9] {
+    ///     dice.add(face);
+    /// }
+    /// assert_eq!(dice.population_variance(), Some(4.0));
+    /// assert_eq!(dice.population_std_dev(), Some(2.0));
+    /// assert_eq!(dice.sample_variance(), Some(32.0 / 7.0));
+    /// ```
+    pub fn population_variance(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.m2 / self.weight)
+    }
+
+    /// Population standard deviation; needs at least one value.
+    pub fn population_std_dev(&self) -> Option<f64> {
+        self.population_variance().map(compat::sqrt)
+    }
+
+    /// Standard error of the mean, the sample standard deviation divided
+    /// by the square root of the count; needs at least two values.
+    pub fn standard_error(&self) -> Option<f64> {
+        Some(compat::sqrt(self.variance()? / self.count as f64))
+    }
+
+    /// The first value added since construction or the last reset.
+    pub fn first(&self) -> Option<f64> {
+        self.first
+    }
+
+    /// Values per second between the earliest and the latest value added
+    /// with [`add_at`](Self::add_at), over the whole stream; values added
+    /// without a timestamp are not counted. `None` until two timestamps
+    /// apart, and again once every value was removed.
+    ///
+    /// `Moving` keeps no per-value timestamps, so it cannot tell how many
+    /// values arrived recently; [`TimeWindowedMoving::rate`] gives the rate
+    /// over a recent window.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let start = Instant::now();
+    /// let mut latency: Moving<u32> = Moving::new();
+    /// for (second, millis) in [(0, 12), (1, 15), (2, 11), (4, 13)] {
+    ///     latency.add_at(millis, start + Duration::from_secs(second));
+    /// }
+    /// assert_eq!(latency.rate(), Some(0.75));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn rate(&self) -> Option<f64> {
+        let (first, last, count) = self.arrivals?;
+        let seconds = last.saturating_duration_since(first).as_secs_f64();
+        (seconds > 0.0).then(|| count.saturating_sub(1) as f64 / seconds)
+    }
+
+    /// When the first value was observed, if it was added with a timestamp.
+    #[cfg(feature = "std")]
+    pub fn first_at(&self) -> Option<Instant> {
+        self.first_at
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.min
+    }
+
+    /// When the minimum was first observed, if it was added with a timestamp.
+    #[cfg(feature = "std")]
+    pub fn min_at(&self) -> Option<Instant> {
+        self.min_at
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.max
+    }
+
+    /// When the maximum was first observed, if it was added with a timestamp.
+    #[cfg(feature = "std")]
+    pub fn max_at(&self) -> Option<Instant> {
+        self.max_at
+    }
+
+    /// The most recently added value.
+    pub fn last(&self) -> Option<f64> {
+        self.last
+    }
+
+    /// When the most recent value was observed, if it was added with a
+    /// timestamp.
+    #[cfg(feature = "std")]
+    pub fn last_at(&self) -> Option<Instant> {
+        self.last_at
+    }
+}
+
+impl Moving<Duration> {
+    /// The mean as a duration; [`mean`](Self::mean) gives it in seconds.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    /// use std::time::Duration;
+    ///
+    /// let mut latency: Moving<Duration> = Moving::new();
+    /// latency.add(Duration::from_millis(120));
+    /// latency.add(Duration::from_millis(80));
+    /// assert_eq!(latency.mean_duration(), Duration::from_millis(100));
+    /// assert_eq!(latency.mean(), 0.1);
+    /// ```
+    pub fn mean_duration(&self) -> Duration {
+        Duration::try_from_secs_f64(self.mean).unwrap_or_default()
+    }
+}
+
+impl<T> AddAssign<T> for Moving<T>
+where
+    T: MovingInput,
+{
+    fn add_assign(&mut self, other: T) {
+        self.add(other);
+    }
+}
+
+impl<T> Extend<T> for Moving<T>
+where
+    T: MovingInput,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        self.add_iter(values);
+    }
+}
+
+impl<T> SubAssign<T> for Moving<T>
+where
+    T: MovingInput,
+{
+    fn sub_assign(&mut self, other: T) {
+        self.remove(other);
+    }
+}
+
+/// Clones every statistic and setting, so a copy can be fed separately,
+/// e.g. to try out a what-if scenario. Closures in ingestion stages are
+/// shared between the copies.
+impl<T> Clone for Moving<T> {
+    fn clone(&self) -> Self {
+        Self {
+            count: self.count,
+            weight: self.weight,
+            mean: self.mean,
+            compensated: self.compensated,
+            m2: self.m2,
+            log2_sum: self.log2_sum,
+            reciprocal_sum: self.reciprocal_sum,
+            positive: self.positive,
+            first: self.first,
+            first_at: self.first_at,
+            last: self.last,
+            last_at: self.last_at,
+            min: self.min,
+            min_at: self.min_at,
+            max: self.max,
+            max_at: self.max_at,
+            arrivals: self.arrivals,
+            history: self.history.clone(),
+            integral: self.integral,
+            held: self.held,
+            held_seconds: self.held_seconds,
+            jitter: self.jitter,
+            jitter_count: self.jitter_count,
+            crossing_reference: self.crossing_reference,
+            crossing_side: self.crossing_side,
+            crossings: self.crossings,
+            streak: self.streak,
+            longest_rising: self.longest_rising,
+            longest_falling: self.longest_falling,
+            missing_policy: self.missing_policy,
+            missing: self.missing,
+            gap: self.gap,
+            max_gap: self.max_gap,
+            non_finite_policy: self.non_finite_policy,
+            non_finite: self.non_finite,
+            overflow_policy: self.overflow_policy,
+            pipeline: self.pipeline.clone(),
+            frozen: self.frozen,
+            ignored: self.ignored,
+            mode: self.mode.clone(),
+            median: self.median.clone(),
+            quantiles: self.quantiles.clone(),
+            threshold: self.threshold.clone(),
+            threshold_reached: self.threshold_reached,
+            observer: self.observer.clone(),
+            phantom: core::marker::PhantomData,
+        }
     }
 }
 
@@ -197,8 +2085,8 @@ impl<T> Deref for Moving<T> {
     }
 }
 
-impl<T> std::fmt::Display for Moving<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<T> core::fmt::Display for Moving<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.mean)
     }
 }
@@ -207,6 +2095,17 @@ impl<T> std::fmt::Display for Moving<T> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn average_durations() {
+        let mut latency: Moving<Duration> = Moving::new();
+        assert_eq!(latency.mean_duration(), Duration::ZERO);
+        for millis in [10, 20, 60] {
+            latency.add(Duration::from_millis(millis));
+        }
+        assert_eq!(latency.mean_duration(), Duration::from_millis(30));
+        assert_eq!(latency.max(), Some(0.06));
+    }
+
     #[test]
     fn add_moving_average() {
         let mut moving_average: Moving<usize> = Moving::new();
@@ -271,6 +2170,769 @@ mod tests {
         assert!(moving_average < f32::MAX)
     }
 
+    #[test]
+    fn custom_input_type() {
+        struct Millis(u64);
+
+        impl MovingInput for Millis {
+            fn to_f64(self) -> f64 {
+                self.0 as f64 / 1000.0
+            }
+        }
+
+        let mut moving_average: Moving<Millis> = Moving::new();
+        moving_average.add(Millis(500));
+        moving_average += Millis(1500);
+        assert_eq!(*moving_average, 1.0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn last_value() {
+        let mut moving_average: Moving<i32> = Moving::new();
+        assert_eq!(moving_average.last(), None);
+        moving_average.add(3);
+        moving_average.add(-7);
+        assert_eq!(moving_average.last(), Some(-7.0));
+        assert_eq!(moving_average.last_at(), None);
+
+        let now = Instant::now();
+        moving_average.add_at(5, now);
+        assert_eq!(moving_average.last(), Some(5.0));
+        assert_eq!(moving_average.last_at(), Some(now));
+
+        moving_average.add(1);
+        assert_eq!(moving_average.last_at(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn rate_counts_only_timestamped_values() {
+        let start = Instant::now();
+        let mut moving_average: Moving<u8> = Moving::new();
+        moving_average.add_at(1, start);
+        moving_average.add_at(2, start + Duration::from_secs(2));
+        moving_average.add(3);
+        moving_average.add(4);
+        assert_eq!(moving_average.rate(), Some(0.5));
+
+        let mut removed: Moving<u8> = Moving::new();
+        removed.add_at(1, start);
+        removed.add_at(2, start + Duration::from_secs(1));
+        removed.remove(1);
+        removed.remove(2);
+        assert_eq!(removed.count(), 0);
+        assert_eq!(removed.rate(), None);
+        removed.add_at(5, start + Duration::from_secs(3));
+        assert_eq!(removed.rate(), None);
+        removed.add_at(5, start + Duration::from_secs(7));
+        assert_eq!(removed.rate(), Some(0.25));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn first_value_until_reset() {
+        let mut moving_average: Moving<u8> = Moving::new();
+        assert_eq!(moving_average.first(), None);
+        let now = Instant::now();
+        moving_average.add_at(4, now);
+        moving_average.add(8);
+        assert_eq!(moving_average.first(), Some(4.0));
+        assert_eq!(moving_average.first_at(), Some(now));
+
+        moving_average.reset();
+        assert_eq!(moving_average.count(), 0);
+        assert_eq!(moving_average, 0);
+        assert_eq!(moving_average.first(), None);
+        moving_average.add(2);
+        assert_eq!(moving_average.first(), Some(2.0));
+        assert_eq!(moving_average.first_at(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn extrema_with_timestamps() {
+        let mut moving_average: Moving<i32> = Moving::new();
+        assert_eq!(moving_average.min(), None);
+        assert_eq!(moving_average.max_at(), None);
+
+        let start = Instant::now();
+        let later = start + std::time::Duration::from_secs(5);
+        moving_average.add_at(5, start);
+        moving_average.add_at(-2, later);
+        moving_average.add_at(-2, later + std::time::Duration::from_secs(1));
+        moving_average.add(3);
+
+        assert_eq!(moving_average.min(), Some(-2.0));
+        assert_eq!(moving_average.min_at(), Some(later));
+        assert_eq!(moving_average.max(), Some(5.0));
+        assert_eq!(moving_average.max_at(), Some(start));
+
+        moving_average.reset();
+        assert_eq!(moving_average.max(), None);
+    }
+
+    #[test]
+    fn seeded_from_prior_state() {
+        let mut moving_average: Moving<u32> = Moving::with_initial(10.0, 3);
+        assert_eq!(moving_average, 10);
+        assert_eq!(moving_average.count(), 3);
+        assert_eq!(moving_average.min(), None);
+        moving_average.add(30);
+        assert_eq!(moving_average, 15);
+        assert_eq!(moving_average.count(), 4);
+
+        let empty: Moving<u32> = Moving::with_initial(99.0, 0);
+        assert_eq!(empty, 0);
+    }
+
+    #[test]
+    fn percent_change_over_history() {
+        let mut moving_average: Moving<u32> = Moving::new().with_history(2);
+        assert_eq!(moving_average.percent_change(1), None);
+        for value in [10, 10, 40, 40] {
+            moving_average.add(value);
+        }
+        assert_eq!(moving_average.percent_change(0), Some(0.0));
+        assert_eq!(moving_average.percent_change(1), Some(25.0));
+        assert_eq!(moving_average.percent_change(2), Some(150.0));
+        assert_eq!(moving_average.percent_change(3), None);
+
+        moving_average.reset();
+        moving_average.add(5);
+        moving_average.add(15);
+        assert_eq!(moving_average.percent_change(1), Some(100.0));
+
+        let untracked: Moving<u32> = Moving::new();
+        assert_eq!(untracked.percent_change(0), None);
+
+        let mut unbounded: Moving<u32> = Moving::new().with_history(usize::MAX);
+        unbounded.add(4);
+        unbounded.add(6);
+        assert_eq!(unbounded.percent_change(1), Some(25.0));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn time_integral() {
+        let mut power: Moving<f64> = Moving::new();
+        let start = Instant::now();
+        power.add_at(100.0, start);
+        assert_eq!(power.integral(), 0.0);
+        power.add_at(200.0, start + std::time::Duration::from_secs(2));
+        power.add_at(200.0, start + std::time::Duration::from_secs(3));
+        assert_eq!(power.integral(), 500.0);
+
+        power.add(1000.0);
+        power.add_at(0.0, start + std::time::Duration::from_secs(10));
+        assert_eq!(power.integral(), 500.0);
+        // 100 for 2s, then 200 for 1s; the untimed add breaks the chain.
+        assert_eq!(power.time_weighted_mean(), Some(400.0 / 3.0));
+        assert_eq!(power.time_weighted_mean_at(start), Some(400.0 / 3.0));
+    }
+
+    #[test]
+    fn jitter_of_successive_values() {
+        let mut latency: Moving<u32> = Moving::new();
+        latency.add(10);
+        assert_eq!(latency.jitter(), None);
+        for value in [14, 12, 12, 20] {
+            latency.add(value);
+        }
+        assert_eq!(latency.jitter(), Some(3.5));
+    }
+
+    #[test]
+    fn crossings_of_running_mean() {
+        let mut signal: Moving<i32> = Moving::new();
+        for value in [0, 10, 0, 10, 5, 5] {
+            signal.add(value);
+        }
+        assert_eq!(signal.crossings(), 2);
+    }
+
+    #[test]
+    fn crossings_of_reference() {
+        let mut signal: Moving<i32> = Moving::new().with_crossing_reference(0.0);
+        for value in [1, -1, 0, -2, 3, 0, 4] {
+            signal.add(value);
+        }
+        assert_eq!(signal.crossings(), 2);
+        signal.reset();
+        signal.add(-1);
+        signal.add(1);
+        assert_eq!(signal.crossings(), 1);
+    }
+
+    #[test]
+    fn streaks() {
+        let mut price: Moving<u32> = Moving::new();
+        price.add(5);
+        assert_eq!(price.current_streak(), None);
+        for value in [6, 7, 8, 4, 3] {
+            price.add(value);
+        }
+        assert_eq!(price.current_streak(), Some((Direction::Falling, 2)));
+        assert_eq!(price.longest_streak(Direction::Rising), 3);
+        assert_eq!(price.longest_streak(Direction::Falling), 2);
+
+        price.add(3);
+        assert_eq!(price.current_streak(), None);
+        price.add(4);
+        assert_eq!(price.current_streak(), Some((Direction::Rising, 1)));
+        assert_eq!(price.longest_streak(Direction::Rising), 3);
+    }
+
+    #[test]
+    fn missing_values_skipped_by_default() {
+        let mut moving_average: Moving<u32> = Moving::new();
+        assert_eq!(moving_average.add_opt(Some(4)), Ok(()));
+        assert_eq!(moving_average.add_opt(None), Ok(()));
+        assert_eq!(moving_average.add_result(Err::<u32, _>("timeout")), Ok(()));
+        assert_eq!(moving_average.add_result(Ok::<_, ()>(8)), Ok(()));
+        assert_eq!(moving_average, 6);
+        assert_eq!(moving_average.count(), 2);
+        assert_eq!(moving_average.missing(), 2);
+    }
+
+    #[test]
+    fn missing_value_policies() {
+        let mut zero: Moving<u32> = Moving::new().with_missing_policy(MissingPolicy::Zero);
+        zero.add(4);
+        assert_eq!(zero.add_opt(None), Ok(()));
+        assert_eq!(zero, 2);
+        assert_eq!(zero.missing(), 1);
+
+        let mut strict: Moving<u32> = Moving::new().with_missing_policy(MissingPolicy::Error);
+        strict.add(4);
+        assert_eq!(strict.add_opt(None), Err(MovingError::MissingValue));
+        assert_eq!(strict, 4);
+        assert_eq!(strict.missing(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "derive")]
+    fn derived_stats_average_each_field() {
+        #[derive(MovingStats)]
+        #[allow(dead_code)]
+        pub(crate) struct Sample {
+            pub cpu: f64,
+            latency: Duration,
+            #[moving(skip)]
+            host: &'static str,
+            pairs: core::primitive::u8,
+        }
+
+        let mut stats = SampleStats::default();
+        for (cpu, millis, pairs) in [(0.25, 10, 1), (0.75, 30, 3)] {
+            stats.add(&Sample {
+                cpu,
+                latency: Duration::from_millis(millis),
+                host: "a",
+                pairs,
+            });
+        }
+        assert_eq!(stats.count(), 2);
+        assert_eq!(stats.cpu.mean(), 0.5);
+        assert_eq!(stats.latency.mean_duration(), Duration::from_millis(20));
+        assert_eq!(stats.pairs.mean(), 2.0);
+        stats.reset();
+        assert_eq!(stats.cpu.count(), 0);
+    }
+
+    #[test]
+    fn non_finite_policies() {
+        let mut accept: Moving<f64> = Moving::new();
+        accept.add(1.0);
+        accept.add(f64::NAN);
+        assert!(accept.mean().is_nan());
+        assert_eq!(accept.non_finite(), 1);
+
+        let mut skip: Moving<f32> = Moving::new().with_non_finite_policy(NonFinitePolicy::Skip);
+        assert_eq!(
+            skip.add_with_result(f32::NEG_INFINITY)
+                .map(|outcome| outcome.mean),
+            Ok(0.0)
+        );
+        skip.add(3.0);
+        assert_eq!((skip.count(), skip.mean(), skip.non_finite()), (1, 3.0, 1));
+        skip.reset();
+        assert_eq!(skip.non_finite(), 0);
+        skip.add(f32::NAN);
+        assert_eq!(skip.count(), 0);
+    }
+
+    #[test]
+    fn overflow_policies() {
+        let mut saturating: Moving<u32> = Moving::from_parts(usize::MAX - 1, 2.0, None, None)
+            .with_overflow_policy(OverflowPolicy::Saturate);
+        saturating.add(2);
+        saturating.add(2);
+        assert_eq!(saturating.count(), usize::MAX);
+        let mut other: Moving<u32> = Moving::new();
+        other.add(2);
+        saturating.merge(&other);
+        assert_eq!(saturating.count(), usize::MAX);
+        assert_eq!(saturating.mean(), 2.0);
+
+        let mut strict: Moving<u32> = Moving::from_parts(usize::MAX, 2.0, None, None);
+        strict.add(100);
+        assert_eq!(strict.max(), None);
+        assert_eq!(strict.mean(), 2.0);
+
+        let mut wrapping: Moving<u32> = Moving::from_parts(usize::MAX, 2.0, None, None)
+            .with_overflow_policy(OverflowPolicy::Wrap);
+        wrapping.add(10);
+        wrapping.add(20);
+        assert_eq!((wrapping.count(), wrapping.mean()), (2, 15.0));
+        assert_eq!(wrapping.variance(), Some(50.0));
+        let mut full: Moving<u32> = Moving::from_parts(usize::MAX, 2.0, None, None)
+            .with_overflow_policy(OverflowPolicy::Wrap);
+        full.merge(&wrapping);
+        assert_eq!((full.count(), full.mean()), (2, 15.0));
+        assert_eq!(full.variance(), Some(50.0));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn gap_policies_fill_missed_samples() {
+        let start = Instant::now();
+        let second = Duration::from_secs(1);
+        let filled = |policy| {
+            let mut moving: Moving<f64> = Moving::new().with_gap_policy(second, policy);
+            moving.add_at(6.0, start);
+            // Slightly late samples do not open a gap.
+            moving.add_at(6.0, start + second * 7 / 5);
+            moving.add_at(0.0, start + second * 4);
+            (moving.count(), moving.mean(), moving.last_at())
+        };
+        assert_eq!(filled(GapPolicy::Skip), (3, 4.0, Some(start + second * 4)));
+        assert_eq!(filled(GapPolicy::Zero).0, 5);
+        assert!((filled(GapPolicy::Zero).1 - 2.4).abs() < 1e-12);
+        assert!((filled(GapPolicy::CarryForward).1 - 4.8).abs() < 1e-12);
+        assert!((filled(GapPolicy::Interpolate).1 - 3.6).abs() < 1e-12);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn gaps_are_not_filled_when_frozen_or_too_long() {
+        let start = Instant::now();
+        let second = Duration::from_secs(1);
+        let mut moving: Moving<f64> = Moving::new()
+            .with_gap_policy(second, GapPolicy::CarryForward)
+            .with_max_gap(3);
+        moving.add_at(1.0, start);
+        moving.add_at(2.0, start + second * 4);
+        assert_eq!((moving.count(), moving.missing()), (5, 3));
+        moving.add_at(3.0, start + second * 9);
+        assert_eq!((moving.count(), moving.missing()), (6, 3));
+        // A year of one-second samples is far over the limit.
+        moving.add_at(4.0, start + second * 3_600 * 24 * 365);
+        assert_eq!(moving.count(), 7);
+        moving.freeze();
+        moving.add_at(5.0, start + second * 3_600 * 24 * 365 * 2);
+        assert_eq!((moving.count(), moving.missing()), (7, 3));
+        assert_eq!(moving.ignored(), 1);
+    }
+
+    #[test]
+    fn valid_range_rejects_implausible_values() {
+        let mut temperature: Moving<f64> = Moving::new().with_valid_range(-50.0, 60.0);
+        assert_eq!(
+            temperature
+                .add_with_result(20.0)
+                .map(|outcome| outcome.mean),
+            Ok(20.0)
+        );
+        assert_eq!(
+            temperature.add_with_result(850.0),
+            Err(MovingError::OutOfRange)
+        );
+        temperature.add(-273.0);
+        temperature.add(60.0);
+        assert_eq!(temperature, 40.0);
+        assert_eq!(temperature.count(), 2);
+        assert_eq!(temperature.rejected(), 2);
+        assert_eq!(temperature.max(), Some(60.0));
+    }
+
+    #[test]
+    fn transform_before_accumulating() {
+        let mut celsius: Moving<f64> = Moving::new()
+            .with_valid_range(-459.67, 1000.0)
+            .with_transform(|fahrenheit| (fahrenheit - 32.0) * 5.0 / 9.0);
+        celsius.add(212.0);
+        celsius.add(32.0);
+        celsius.add(-500.0);
+        assert_eq!(celsius, 50.0);
+        assert_eq!(celsius.max(), Some(100.0));
+        assert_eq!(celsius.rejected(), 1);
+
+        celsius.reset();
+        celsius.add(50.0);
+        assert_eq!(celsius, 10.0);
+    }
+
+    #[test]
+    fn frozen_adds_are_ignored() {
+        let mut moving_average: Moving<u32> = Moving::new();
+        moving_average.add(10);
+        moving_average.freeze();
+        assert!(moving_average.is_frozen());
+        moving_average.add(1000);
+        assert_eq!(
+            moving_average.add_with_result(1000),
+            Err(MovingError::Frozen)
+        );
+        assert_eq!(moving_average.add_opt(None), Err(MovingError::Frozen));
+        assert_eq!(moving_average, 10);
+        assert_eq!(moving_average.ignored(), 3);
+        assert_eq!(moving_average.missing(), 0);
+
+        moving_average.unfreeze();
+        moving_average.add(20);
+        assert_eq!(moving_average, 15);
+    }
+
+    #[test]
+    fn mode_tracking() {
+        let mut untracked: Moving<u32> = Moving::new();
+        untracked.add(1);
+        assert_eq!(untracked.mode(), None);
+        assert_eq!(untracked.mode_backend(), None);
+
+        let mut moving_average: Moving<u32> = Moving::new().with_mode_tracking();
+        assert_eq!(moving_average.mode(), None);
+        for value in [3, 7, 7, 1, 3, 7] {
+            moving_average.add(value);
+        }
+        assert_eq!(moving_average.mode(), Some(7.0));
+        assert_eq!(moving_average.mode_backend(), Some(ModeBackend::Exact));
+        assert_eq!(moving_average.estimated_frequency(3), Some(2));
+        assert_eq!(
+            moving_average.frequencies(),
+            vec![(1.0, 1), (3.0, 2), (7.0, 3)]
+        );
+        assert_eq!(untracked.frequencies(), vec![]);
+        assert_eq!(moving_average.top_k(2), vec![(7.0, 3), (3.0, 2)]);
+        assert_eq!(moving_average.top_k(10).len(), 3);
+        assert_eq!(untracked.estimated_frequency(1), None);
+        moving_average.reset();
+        assert_eq!(moving_average.mode(), None);
+    }
+
+    #[test]
+    fn memory_budget_degrades_mode() {
+        let mut moving_average: Moving<u32> = Moving::new().with_memory_budget(1024);
+        for value in 0..10_000 {
+            moving_average.add(value % 1000);
+        }
+        assert!(moving_average.is_mode_degraded());
+        assert!(matches!(
+            moving_average.mode_backend(),
+            Some(ModeBackend::Binned { .. })
+        ));
+        assert!(moving_average.mode().is_some());
+
+        moving_average.reset();
+        assert!(!moving_average.is_mode_degraded());
+    }
+
+    #[test]
+    fn mad_outlier_rejection() {
+        let mut moving_average: Moving<i32> =
+            Moving::new().with_outlier_rejection(OutlierRule::Mad(5.0));
+        for value in [10, 12, 11, 500, 9, 10, -400, 11] {
+            moving_average.add(value);
+        }
+        assert_eq!(moving_average.count(), 6);
+        assert_eq!(moving_average.median(), Some(10.5));
+        assert_eq!(
+            moving_average.add_with_result(90),
+            Err(MovingError::Outlier)
+        );
+        assert_eq!(moving_average.stages()[0].dropped, 3);
+    }
+
+    #[test]
+    fn accumulators_are_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Moving<f64>>();
+        assert_send_sync::<WindowedMoving<u32>>();
+        assert_send_sync::<Ema<i64>>();
+        assert_send_sync::<Quantile<f32>>();
+        assert_send_sync::<MovingPair<f64>>();
+    }
+
+    #[test]
+    fn clones_are_independent() {
+        let mut original: Moving<i32> = Moving::new().with_median().with_mode_tracking();
+        for value in [3, 1, 3] {
+            original.add(value);
+        }
+        let mut fork = original.clone();
+        assert!(fork.eq_state(&original));
+        fork.add(9);
+        assert_eq!(original.count(), 3);
+        assert_eq!(original.median(), Some(3.0));
+        assert_eq!(fork.median(), Some(3.0));
+        assert_eq!(fork.max(), Some(9.0));
+        fork.remove(9);
+        // Same count and mean, different extremes.
+        assert!(!fork.eq_state(&original));
+    }
+
+    #[test]
+    fn geometric_and_harmonic_means() {
+        let mut moving_average: Moving<f64> = Moving::new();
+        assert_eq!(moving_average.geometric_mean(), None);
+        for value in [1.0, 4.0, 16.0] {
+            moving_average.add(value);
+        }
+        assert!((moving_average.geometric_mean().unwrap() - 4.0).abs() < 1e-12);
+        assert!((moving_average.harmonic_mean().unwrap() - 16.0 / 7.0).abs() < 1e-12);
+
+        let mut other: Moving<f64> = Moving::new();
+        other.add(0.0);
+        moving_average.merge(&other);
+        assert_eq!(moving_average.geometric_mean(), None);
+        assert_eq!(moving_average.harmonic_mean(), None);
+        moving_average.remove(0.0);
+        moving_average.remove(16.0);
+        assert!((moving_average.geometric_mean().unwrap() - 2.0).abs() < 1e-12);
+        assert!((moving_average.harmonic_mean().unwrap() - 1.6).abs() < 1e-12);
+    }
+
+    #[test]
+    fn variance_and_std_dev() {
+        let mut moving_average: Moving<u32> = Moving::new();
+        moving_average.add(2);
+        assert_eq!(moving_average.variance(), None);
+        assert_eq!(moving_average.population_variance(), Some(0.0));
+        assert_eq!(moving_average.standard_error(), None);
+        for value in [4, 4, 4, 5, 5, 7, 9] {
+            moving_average.add(value);
+        }
+        assert!((moving_average.variance().unwrap() - 32.0 / 7.0).abs() < 1e-12);
+        assert!((moving_average.std_dev().unwrap() - (32.0_f64 / 7.0).sqrt()).abs() < 1e-12);
+        assert!((moving_average.standard_error().unwrap() - 0.75593).abs() < 1e-5);
+        moving_average.remove(9);
+        moving_average.remove(7);
+        assert!((moving_average.variance().unwrap() - 1.2).abs() < 1e-12);
+        moving_average.reset();
+        assert_eq!(moving_average.std_dev(), None);
+    }
+
+    #[test]
+    fn median_follows_adds_and_removes() {
+        let mut moving_average: Moving<i32> = Moving::new();
+        moving_average.add(1);
+        assert_eq!(moving_average.median(), None);
+
+        let mut moving_average: Moving<i32> = Moving::new().with_median();
+        for value in [7, 1, 3, 1000] {
+            moving_average.add(value);
+        }
+        assert_eq!(moving_average.median(), Some(5.0));
+        moving_average.remove(1000);
+        assert_eq!(moving_average.median(), Some(3.0));
+        moving_average.reset();
+        assert_eq!(moving_average.median(), None);
+        moving_average.add(4);
+        assert_eq!(moving_average.median(), Some(4.0));
+    }
+
+    #[test]
+    fn merge_matches_single_stream() {
+        let values = [3, 9, 4, 4, 12, -5, 4, 7];
+        let mut single: Moving<i32> = Moving::new().with_mode_tracking().with_median();
+        let mut left: Moving<i32> = Moving::new().with_mode_tracking().with_median();
+        let mut right: Moving<i32> = Moving::new().with_mode_tracking().with_median();
+        for (i, value) in values.into_iter().enumerate() {
+            single.add(value);
+            if i < 3 {
+                left.add(value);
+            } else {
+                right.add(value);
+            }
+        }
+        left.merge(&right);
+        assert_eq!(left.count(), single.count());
+        assert!((left.mean() - single.mean()).abs() < 1e-12);
+        assert!((left.variance().unwrap() - single.variance().unwrap()).abs() < 1e-12);
+        assert_eq!(left.min(), Some(-5.0));
+        assert_eq!(left.max(), Some(12.0));
+        assert_eq!(left.mode(), Some(4.0));
+        assert_eq!(left.median(), single.median());
+
+        let mut empty: Moving<i32> = Moving::new();
+        empty.merge(&Moving::new());
+        assert_eq!(empty.count(), 0);
+        empty.merge(&single);
+        assert_eq!(empty.mean(), single.mean());
+    }
+
+    #[test]
+    fn bulk_adds() {
+        let mut bulk: Moving<i64> = Moving::new().with_valid_range(0.0, 100.0);
+        bulk.add_slice(&[5, 500, 15]);
+        bulk.extend([10, -1]);
+        assert_eq!(bulk.count(), 3);
+        assert_eq!(bulk, 10);
+        assert_eq!(bulk.rejected(), 2);
+    }
+
+    #[test]
+    fn threshold_reports_but_records() {
+        let mut moving_average: Moving<i32> = Moving::new_with_threshold(10.0);
+        assert_eq!(
+            moving_average
+                .add_with_result(8)
+                .map(|outcome| outcome.mean),
+            Ok(8.0)
+        );
+        let Err(MovingError::ThresholdReached(breach)) = moving_average.add_with_result(20) else {
+            panic!("expected the threshold to be reached");
+        };
+        assert_eq!(breach.statistic, Statistic::Mean);
+        assert_eq!(
+            (breach.observed, breach.bound, breach.value),
+            (14.0, 10.0, 20.0)
+        );
+        assert_eq!(moving_average.count(), 2);
+        assert!(matches!(
+            moving_average.remove_with_result(8),
+            Err(MovingError::ThresholdReached(_))
+        ));
+        assert_eq!(moving_average.remove_with_result(20), Ok(0.0));
+        moving_average.reset();
+        assert_eq!(moving_average.threshold(), Some(&Threshold::Upper(10.0)));
+    }
+
+    #[test]
+    fn thresholds_on_other_statistics() {
+        let mut budget: Moving<u8> = Moving::new().with_threshold_on(Statistic::Count, 3.0);
+        assert!(budget.add_with_result(1).is_ok());
+        assert!(budget.add_with_result(1).is_ok());
+        assert!(matches!(
+            budget.add_with_result(1),
+            Err(MovingError::ThresholdReached(_))
+        ));
+        assert_eq!(budget.threshold_statistic(), Some(Statistic::Count));
+
+        let mut spread: Moving<i32> = Moving::new().with_threshold_on(Statistic::Variance, 50.0);
+        assert!(spread.add_with_result(0).is_ok());
+        assert!(matches!(
+            spread.add_with_result(10),
+            Err(MovingError::ThresholdReached(_))
+        ));
+
+        let mut untracked: Moving<i32> = Moving::new().with_threshold_on(Statistic::Mode, 0.0);
+        assert!(untracked.add_with_result(5).is_ok());
+        let mut tracked: Moving<i32> = Moving::new()
+            .with_mode_tracking()
+            .with_threshold_on(Statistic::Mode, 5.0);
+        assert!(matches!(
+            tracked.add_with_result(5),
+            Err(MovingError::ThresholdReached(_))
+        ));
+    }
+
+    #[test]
+    fn hysteresis_threshold() {
+        let mut moving_average: Moving<i32> = Moving::new().with_threshold(Threshold::Hysteresis {
+            trigger: 10.0,
+            clear: 5.0,
+        });
+        assert!(moving_average.add_with_result(12).is_err());
+        assert!(moving_average.is_threshold_reached());
+        assert!(moving_average.add_with_result(4).is_err());
+        assert!(moving_average.add_with_result(-1).is_ok());
+        assert!(!moving_average.is_threshold_reached());
+        assert!(moving_average.add_with_result(22).is_ok());
+    }
+
+    #[test]
+    fn weighted_adds() {
+        let mut weighted: Moving<u32> = Moving::new();
+        assert_eq!(weighted.add_weighted(10, 3.0), Ok(10.0));
+        assert_eq!(weighted.add_weighted(20, 1.0), Ok(12.5));
+        assert_eq!(
+            weighted.add_weighted(20, 0.0),
+            Err(MovingError::InvalidWeight)
+        );
+        assert_eq!(weighted.count(), 2);
+        // Population variance 18.75, corrected by 2 / 1.
+        assert_eq!(weighted.variance(), Some(37.5));
+
+        let mut repeated: Moving<u32> = Moving::new();
+        repeated.add_slice(&[10, 10, 10, 20]);
+        weighted.merge(&repeated);
+        assert_eq!(weighted.mean(), 12.5);
+        assert_eq!(weighted.total_weight(), 8.0);
+    }
+
+    #[test]
+    fn weighted_mode_and_unweighted_statistics() {
+        let mut sizes: Moving<u32> = Moving::new().with_mode_tracking();
+        sizes.add_slice(&[1, 1, 1]);
+        sizes.add_weighted(5, 4.0).unwrap();
+        assert_eq!(sizes.mode(), Some(5.0));
+        assert_eq!(sizes.modes(), vec![(5.0, 1)]);
+        sizes.add(5);
+        assert_eq!(sizes.estimated_frequency(5), Some(2));
+
+        let mut median: Moving<u32> = Moving::new().with_median();
+        assert_eq!(
+            median.add_weighted(5, 4.0),
+            Err(MovingError::UnweightedStatistic)
+        );
+        assert_eq!(median.add_weighted(5, 1.0), Ok(5.0));
+        let mut quantile: Moving<u32> = Moving::new().with_quantile(0.9);
+        assert_eq!(
+            quantile.add_weighted(5, 0.5),
+            Err(MovingError::UnweightedStatistic)
+        );
+        assert_eq!(quantile.count(), 0);
+    }
+
+    #[test]
+    fn compensated_mean_survives_cancellation() {
+        let mut moving_average: Moving<f64> = Moving::new().with_compensated_mean();
+        for _ in 0..1000 {
+            moving_average.add_slice(&[0.1, 1e20, 0.1, -1e20]);
+        }
+        assert!((moving_average.mean() - 0.05).abs() < 1e-12);
+        moving_average.remove(0.1);
+        moving_average.remove(0.1);
+        assert!((moving_average.mean() - 199.8 / 3998.0).abs() < 1e-12);
+        moving_average.reset();
+        moving_average.add(3.0);
+        assert_eq!(moving_average.mean(), 3.0);
+    }
+
+    #[test]
+    fn remove_values() {
+        let mut moving_average: Moving<i32> = Moving::new().with_mode_tracking();
+        for value in [4, 4, 8, 8, 16] {
+            moving_average.add(value);
+        }
+        assert_eq!(moving_average.remove_with_result(8), Ok(8.0));
+        moving_average -= 8;
+        assert_eq!(moving_average.count(), 3);
+        assert_eq!(moving_average, 8);
+        assert_eq!(moving_average.mode(), Some(4.0));
+        moving_average.remove(4);
+        moving_average.remove(4);
+        moving_average.remove(16);
+        assert_eq!(moving_average.count(), 0);
+        assert_eq!(moving_average.mode(), None);
+        assert_eq!(
+            moving_average.remove_with_result(1),
+            Err(MovingError::Empty)
+        );
+    }
+
     #[test]
     fn many_operations() {
         let mut moving_average: Moving<_> = Moving::new();
@@ -0,0 +1,440 @@
+use alloc::collections::{BTreeMap, BinaryHeap};
+use alloc::vec::Vec;
+use core::cmp::{Ordering, Reverse};
+use core::ops::Bound;
+
+use crate::compat::Map as HashMap;
+
+/// An `f64` ordered by [`f64::total_cmp`], with `-0.0` folded into `0.0`.
+#[derive(Debug, Clone, Copy)]
+struct Sample(f64);
+
+impl Sample {
+    fn new(value: f64) -> Self {
+        Self(value + 0.0)
+    }
+
+    fn key(self) -> u64 {
+        self.0.to_bits()
+    }
+}
+
+impl PartialEq for Sample {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Sample {}
+
+impl PartialOrd for Sample {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Sample {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Mean of the values left after dropping `fraction` of them, rounded down,
+/// from each end; `fraction` is clamped to `0.0..=0.5`. `None` when no
+/// values are left.
+pub(crate) fn trimmed_mean(mut counts: Vec<(f64, usize)>, fraction: f64) -> Option<f64> {
+    counts.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let len: usize = counts.iter().map(|(_, count)| count).sum();
+    let fraction = if fraction.is_nan() {
+        0.0
+    } else {
+        fraction.clamp(0.0, 0.5)
+    };
+    let trim = (len as f64 * fraction) as usize;
+    let kept = len.checked_sub(2 * trim).filter(|kept| *kept > 0)?;
+    let (mut skipped, mut sum) = (0, 0.0);
+    for (value, count) in counts {
+        let start = skipped.max(trim);
+        let end = (skipped + count).min(trim + kept);
+        if end > start {
+            sum += value * (end - start) as f64;
+        }
+        skipped += count;
+    }
+    Some(sum / kept as f64)
+}
+
+/// The distinct values from `low` to `high`, holding `count` values in
+/// all: the values nearest the median, which set the median absolute
+/// deviation.
+#[derive(Debug, Clone, Copy)]
+struct Spread {
+    low: Sample,
+    high: Sample,
+    count: usize,
+}
+
+/// Exact running median backing [`Moving::median`](crate::Moving::median).
+///
+/// The lower half of the values sits in a max-heap and the upper half in a
+/// min-heap, so the median is read off the tops. Removed values are deleted
+/// lazily once they reach the top of their heap.
+///
+/// The median absolute deviation is kept up to date alongside: the half of
+/// the values nearest the median form a range of the sorted values, which
+/// only moves a few values when one is added or removed.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MedianTracker {
+    low: BinaryHeap<Sample>,
+    high: BinaryHeap<Reverse<Sample>>,
+    low_len: usize,
+    high_len: usize,
+    removed_low: HashMap<u64, usize>,
+    removed_high: HashMap<u64, usize>,
+    live: BTreeMap<Sample, usize>,
+    spread: Option<Spread>,
+    mad: Option<f64>,
+}
+
+impl MedianTracker {
+    pub(crate) fn add(&mut self, value: f64) {
+        let sample = Sample::new(value);
+        *self.live.entry(sample).or_insert(0) += 1;
+        if self.low.peek().is_none_or(|top| sample <= *top) {
+            self.low.push(sample);
+            self.low_len += 1;
+        } else {
+            self.high.push(Reverse(sample));
+            self.high_len += 1;
+        }
+        if let Some(spread) = &mut self.spread {
+            if spread.low <= sample && sample <= spread.high {
+                spread.count += 1;
+            }
+        }
+        self.rebalance();
+        self.update_spread();
+    }
+
+    pub(crate) fn merge(&mut self, other: &MedianTracker) {
+        for (sample, count) in &other.live {
+            for _ in 0..*count {
+                self.add(sample.0);
+            }
+        }
+    }
+
+    /// Median of the distances between each value and the median.
+    pub(crate) fn median_absolute_deviation(&self) -> Option<f64> {
+        self.mad
+    }
+
+    pub(crate) fn trimmed_mean(&self, fraction: f64) -> Option<f64> {
+        trimmed_mean(self.counts(), fraction)
+    }
+
+    /// Every value with its multiplicity.
+    pub(crate) fn counts(&self) -> Vec<(f64, usize)> {
+        self.live
+            .iter()
+            .map(|(sample, count)| (sample.0, *count))
+            .collect()
+    }
+
+    /// Takes back one occurrence of `value`; values that were never added
+    /// are ignored.
+    pub(crate) fn remove(&mut self, value: f64) {
+        let sample = Sample::new(value);
+        match self.live.get_mut(&sample) {
+            Some(count) if *count > 1 => *count -= 1,
+            Some(_) => {
+                self.live.remove(&sample);
+            }
+            None => return,
+        }
+        if let Some(spread) = self.spread {
+            if spread.low <= sample && sample <= spread.high {
+                let low = if self.live.contains_key(&spread.low) {
+                    Some(spread.low)
+                } else {
+                    self.above(spread.low)
+                };
+                let high = if self.live.contains_key(&spread.high) {
+                    Some(spread.high)
+                } else {
+                    self.below(spread.high)
+                };
+                self.spread = match (low, high) {
+                    (Some(low), Some(high)) if spread.count > 1 => Some(Spread {
+                        low,
+                        high,
+                        count: spread.count - 1,
+                    }),
+                    _ => None,
+                };
+            }
+        }
+        // Everything in `high` is at least the top of `low`, so a value at
+        // or below that top always has a copy in `low`.
+        if self.low.peek().is_some_and(|top| sample <= *top) {
+            *self.removed_low.entry(sample.key()).or_insert(0) += 1;
+            self.low_len -= 1;
+        } else {
+            *self.removed_high.entry(sample.key()).or_insert(0) += 1;
+            self.high_len -= 1;
+        }
+        self.prune();
+        self.rebalance();
+        self.update_spread();
+    }
+
+    pub(crate) fn median(&self) -> Option<f64> {
+        let low = self.low.peek()?.0;
+        if self.low_len > self.high_len {
+            return Some(low);
+        }
+        let Reverse(high) = self.high.peek()?;
+        Some((low + high.0) / 2.0)
+    }
+
+    /// Keeps `low` holding the extra value when the count is odd.
+    fn rebalance(&mut self) {
+        while self.low_len > self.high_len + 1 {
+            if let Some(sample) = self.low.pop() {
+                self.low_len -= 1;
+                self.high.push(Reverse(sample));
+                self.high_len += 1;
+            }
+            self.prune();
+        }
+        while self.high_len > self.low_len {
+            if let Some(Reverse(sample)) = self.high.pop() {
+                self.high_len -= 1;
+                self.low.push(sample);
+                self.low_len += 1;
+            }
+            self.prune();
+        }
+    }
+
+    /// Moves the range of values nearest the median until it holds the
+    /// closest half of them, then reads the median absolute deviation off
+    /// its ends.
+    fn update_spread(&mut self) {
+        let (Some(median), Some(start)) = (self.median(), self.low.peek().copied()) else {
+            self.spread = None;
+            self.mad = None;
+            return;
+        };
+        let len = self.low_len + self.high_len;
+        // The first `half` distances in ascending order cover the middle
+        // one, or the lower of the two middle ones for an even count.
+        let half = len.div_ceil(2);
+        let distance = |sample: Sample| (sample.0 - median).abs();
+        let widest = |spread: &Spread| {
+            let (low, high) = (distance(spread.low), distance(spread.high));
+            if low.total_cmp(&high).is_ge() {
+                low
+            } else {
+                high
+            }
+        };
+        let mut spread = self.spread.unwrap_or(Spread {
+            low: start,
+            high: start,
+            count: self.live[&start],
+        });
+        loop {
+            let outside = [self.below(spread.low), self.above(spread.high)]
+                .into_iter()
+                .flatten()
+                .min_by(|a, b| distance(*a).total_cmp(&distance(*b)));
+            let nearer =
+                outside.is_some_and(|next| distance(next).total_cmp(&widest(&spread)).is_lt());
+            if spread.count < half || nearer {
+                let next = outside.expect("fewer values in the range than in total");
+                if next < spread.low {
+                    spread.low = next;
+                } else {
+                    spread.high = next;
+                }
+                spread.count += self.live[&next];
+                continue;
+            }
+            if spread.low == spread.high {
+                break;
+            }
+            let (far, inner) = if distance(spread.low).total_cmp(&widest(&spread)).is_eq() {
+                (spread.low, self.above(spread.low))
+            } else {
+                (spread.high, self.below(spread.high))
+            };
+            let copies = self.live[&far];
+            if spread.count - copies < half {
+                break;
+            }
+            let inner = inner.expect("the range holds at least two values");
+            if far == spread.low {
+                spread.low = inner;
+            } else {
+                spread.high = inner;
+            }
+            spread.count -= copies;
+        }
+        let widest = widest(&spread);
+        self.mad = Some(if len % 2 == 1 || spread.count > half {
+            widest
+        } else {
+            // Nothing outside the range is nearer than its ends.
+            let next = [self.below(spread.low), self.above(spread.high)]
+                .into_iter()
+                .flatten()
+                .map(distance)
+                .min_by(f64::total_cmp)
+                .expect("an even count leaves values outside the range");
+            (widest + next) / 2.0
+        });
+        self.spread = Some(spread);
+    }
+
+    /// The next smaller distinct value.
+    fn below(&self, sample: Sample) -> Option<Sample> {
+        self.live
+            .range(..sample)
+            .next_back()
+            .map(|(below, _)| *below)
+    }
+
+    /// The next larger distinct value.
+    fn above(&self, sample: Sample) -> Option<Sample> {
+        self.live
+            .range((Bound::Excluded(sample), Bound::Unbounded))
+            .next()
+            .map(|(above, _)| *above)
+    }
+
+    /// Drops removed values sitting on top of either heap.
+    fn prune(&mut self) {
+        while let Some(top) = self.low.peek() {
+            if !take_removed(&mut self.removed_low, top.key()) {
+                break;
+            }
+            self.low.pop();
+        }
+        while let Some(Reverse(top)) = self.high.peek() {
+            if !take_removed(&mut self.removed_high, top.key()) {
+                break;
+            }
+            self.high.pop();
+        }
+    }
+}
+
+fn take_removed(removed: &mut HashMap<u64, usize>, key: u64) -> bool {
+    match removed.get_mut(&key) {
+        Some(count) if *count > 1 => *count -= 1,
+        Some(_) => {
+            removed.remove(&key);
+        }
+        None => return false,
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_absolute_deviation() {
+        let mut tracker = MedianTracker::default();
+        assert_eq!(tracker.median_absolute_deviation(), None);
+        for value in [1.0, 1.0, 2.0, 2.0, 4.0, 6.0, 9.0] {
+            tracker.add(value);
+        }
+        assert_eq!(tracker.median_absolute_deviation(), Some(1.0));
+        tracker.add(10.0);
+        assert_eq!(tracker.median_absolute_deviation(), Some(2.0));
+    }
+
+    #[test]
+    fn median_absolute_deviation_follows_adds_and_removes() {
+        let brute_force = |values: &[f64]| {
+            let mut sorted = values.to_vec();
+            sorted.sort_by(f64::total_cmp);
+            let n = sorted.len();
+            let median_of = |sorted: &[f64]| match n {
+                0 => None,
+                n if n % 2 == 1 => Some(sorted[n / 2]),
+                n => Some((sorted[n / 2 - 1] + sorted[n / 2]) / 2.0),
+            };
+            let median = median_of(&sorted)?;
+            let mut distances: Vec<f64> = sorted.iter().map(|v| (v - median).abs()).collect();
+            distances.sort_by(f64::total_cmp);
+            median_of(&distances)
+        };
+        let mut tracker = MedianTracker::default();
+        let mut values = Vec::new();
+        for i in 0..300 {
+            let value = ((i * 37) % 101) as f64 - if i % 7 == 0 { 500.0 } else { 0.0 };
+            tracker.add(value);
+            values.push(value);
+            if i % 3 == 0 {
+                let removed = values.remove((i * 13) % values.len());
+                tracker.remove(removed);
+            }
+            assert_eq!(tracker.median_absolute_deviation(), brute_force(&values));
+        }
+        while let Some(value) = values.pop() {
+            tracker.remove(value);
+            assert_eq!(tracker.median_absolute_deviation(), brute_force(&values));
+        }
+    }
+
+    #[test]
+    fn trims_both_ends() {
+        let counts = vec![(100.0, 1), (2.0, 3), (-50.0, 1), (4.0, 5)];
+        assert_eq!(trimmed_mean(counts.clone(), 0.1), Some(3.25));
+        assert_eq!(trimmed_mean(counts.clone(), 0.0), Some(7.6));
+        assert_eq!(trimmed_mean(counts.clone(), 0.5), None);
+        assert_eq!(trimmed_mean(vec![(7.0, 1)], 0.5), Some(7.0));
+        assert_eq!(trimmed_mean(Vec::new(), 0.1), None);
+    }
+
+    #[test]
+    fn median_of_odd_and_even_counts() {
+        let mut tracker = MedianTracker::default();
+        assert_eq!(tracker.median(), None);
+        for (value, median) in [
+            (5.0, 5.0),
+            (1.0, 3.0),
+            (100.0, 5.0),
+            (2.0, 3.5),
+            (-0.0, 2.0),
+        ] {
+            tracker.add(value);
+            assert_eq!(tracker.median(), Some(median));
+        }
+    }
+
+    #[test]
+    fn remove_matches_recomputed_median() {
+        let mut tracker = MedianTracker::default();
+        let mut values: Vec<f64> = (0..40).map(|i| ((i * 17) % 23) as f64).collect();
+        for value in &values {
+            tracker.add(*value);
+        }
+        tracker.remove(1000.0);
+        while let Some(value) = values.pop() {
+            let mut sorted = values.clone();
+            sorted.sort_by(f64::total_cmp);
+            tracker.remove(value);
+            let expected = match sorted.len() {
+                0 => None,
+                n if n % 2 == 1 => Some(sorted[n / 2]),
+                n => Some((sorted[n / 2 - 1] + sorted[n / 2]) / 2.0),
+            };
+            assert_eq!(tracker.median(), expected);
+        }
+    }
+}
@@ -0,0 +1,680 @@
+use alloc::boxed::Box;
+#[cfg(feature = "count-min")]
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use crate::compat::{self, Map as HashMap};
+#[cfg(feature = "count-min")]
+use crate::CountMinSketch;
+
+/// Most recent distinct values remembered alongside a count-min sketch,
+/// which cannot enumerate its values to rebuild the mode.
+#[cfg(feature = "count-min")]
+const SKETCH_CANDIDATES: usize = 16;
+
+/// Approximate heap cost of one distinct value in a frequency map,
+/// including the hash table's spare capacity.
+pub(crate) const ENTRY_BYTES: usize = 2 * core::mem::size_of::<(u64, Tally)>();
+
+/// How value frequencies are counted for the mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModeBackend {
+    /// Every distinct value is counted separately.
+    Exact,
+    /// Values are counted per bin of the given width; the mode is reported
+    /// as the centre of the most frequent bin.
+    Binned { width: f64 },
+    /// Values are snapped to the nearest multiple of `epsilon`, so readings
+    /// within `epsilon / 2` of the same multiple count together; the mode
+    /// is reported as that multiple.
+    Tolerance { epsilon: f64 },
+    /// At most `capacity` values are counted with the space-saving
+    /// algorithm: a new value replaces the least frequent one and inherits
+    /// its count. Memory stays constant and frequent values are kept, but
+    /// counts may be overestimated by up to the smallest tracked count.
+    SpaceSaving { capacity: usize },
+    /// Counts are estimated with a [`CountMinSketch`] of `depth` rows of
+    /// `width` cells. Memory stays constant however many values are
+    /// distinct; the mode is the most frequent value seen among recent
+    /// candidates and frequencies may be overestimated.
+    #[cfg(feature = "count-min")]
+    CountMin { width: usize, depth: usize },
+}
+
+/// How often a value was counted and the weight it accumulated.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Tally {
+    count: usize,
+    weight: f64,
+}
+
+impl Tally {
+    /// Ordering used for the mode: by weight, then by count.
+    fn rank(&self, other: &Tally) -> core::cmp::Ordering {
+        self.weight
+            .total_cmp(&other.weight)
+            .then(self.count.cmp(&other.count))
+    }
+
+    /// A tally of unit weights, as a count-min sketch estimates it.
+    #[cfg(feature = "count-min")]
+    fn occurrences(count: usize) -> Self {
+        Tally {
+            count,
+            weight: count as f64,
+        }
+    }
+}
+
+impl core::ops::AddAssign for Tally {
+    fn add_assign(&mut self, other: Tally) {
+        self.count += other.count;
+        self.weight += other.weight;
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Counts {
+    Exact(HashMap<u64, Tally>),
+    Binned {
+        width: f64,
+        origin: f64,
+        bins: HashMap<i64, Tally>,
+    },
+    SpaceSaving {
+        capacity: usize,
+        counters: HashMap<u64, Tally>,
+    },
+    #[cfg(feature = "count-min")]
+    CountMin(CountMinSketch),
+}
+
+impl Counts {
+    fn new(backend: ModeBackend) -> Self {
+        match backend {
+            ModeBackend::Exact => Counts::Exact(HashMap::new()),
+            ModeBackend::Binned { width } | ModeBackend::Tolerance { epsilon: width }
+                if !(width.is_finite() && width > 0.0) =>
+            {
+                Counts::Exact(HashMap::new())
+            }
+            ModeBackend::Binned { width } => Counts::Binned {
+                width,
+                origin: 0.0,
+                bins: HashMap::new(),
+            },
+            ModeBackend::Tolerance { epsilon } => Counts::Binned {
+                width: epsilon,
+                origin: -epsilon / 2.0,
+                bins: HashMap::new(),
+            },
+            ModeBackend::SpaceSaving { capacity } => Counts::SpaceSaving {
+                capacity: capacity.max(1),
+                counters: HashMap::new(),
+            },
+            #[cfg(feature = "count-min")]
+            ModeBackend::CountMin { width, depth } => {
+                Counts::CountMin(CountMinSketch::new(width, depth))
+            }
+        }
+    }
+}
+
+/// Frequency counts backing [`Moving::mode`](crate::Moving::mode).
+#[derive(Debug, Clone)]
+pub(crate) struct ModeTracker {
+    counts: Counts,
+    configured: ModeBackend,
+    budget: Option<usize>,
+    degraded: bool,
+    max: Tally,
+    /// The mode: of the values at `max`, the one closest to the mean when
+    /// the tie formed.
+    leader: Option<f64>,
+    #[cfg(feature = "count-min")]
+    recent: VecDeque<f64>,
+}
+
+impl Default for ModeTracker {
+    fn default() -> Self {
+        Self {
+            counts: Counts::Exact(HashMap::new()),
+            configured: ModeBackend::Exact,
+            budget: None,
+            degraded: false,
+            max: Tally::default(),
+            leader: None,
+            #[cfg(feature = "count-min")]
+            recent: VecDeque::new(),
+        }
+    }
+}
+
+pub(crate) fn key(value: f64) -> u64 {
+    if value.is_nan() {
+        f64::NAN.to_bits()
+    } else {
+        (value + 0.0).to_bits()
+    }
+}
+
+fn bin(value: f64, width: f64, origin: f64) -> i64 {
+    compat::floor((value - origin) / width) as i64
+}
+
+fn centre(bin: i64, width: f64, origin: f64) -> f64 {
+    origin + (bin as f64 + 0.5) * width
+}
+
+/// Takes one occurrence of weight one from the tally under `key`, dropping
+/// it at zero; returns the tally before.
+fn take_one<K: core::hash::Hash + Ord>(counts: &mut HashMap<K, Tally>, key: K) -> Option<Tally> {
+    let tally = counts.get_mut(&key)?;
+    let before = *tally;
+    tally.count -= 1;
+    tally.weight = (tally.weight - 1.0).max(0.0);
+    if tally.count == 0 {
+        counts.remove(&key);
+    }
+    Some(before)
+}
+
+impl ModeTracker {
+    pub(crate) fn set_budget(&mut self, bytes: usize) {
+        self.budget = Some(bytes);
+        self.enforce_budget(self.reference());
+    }
+
+    /// Switches to counting with `backend`, carrying over the counts so
+    /// far.
+    pub(crate) fn set_backend(&mut self, backend: ModeBackend) {
+        let cleared = self.cleared();
+        let previous = core::mem::replace(self, cleared);
+        self.configured = backend;
+        self.counts = Counts::new(backend);
+        self.merge(&previous, previous.reference());
+    }
+
+    /// Same configuration, without any counts.
+    pub(crate) fn cleared(&self) -> Self {
+        Self {
+            counts: Counts::new(self.configured),
+            configured: self.configured,
+            budget: self.budget,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn backend(&self) -> ModeBackend {
+        match &self.counts {
+            Counts::Exact(_) => ModeBackend::Exact,
+            Counts::Binned { width, origin, .. } if *origin == -*width / 2.0 => {
+                ModeBackend::Tolerance { epsilon: *width }
+            }
+            Counts::Binned { width, .. } => ModeBackend::Binned { width: *width },
+            Counts::SpaceSaving { capacity, .. } => ModeBackend::SpaceSaving {
+                capacity: *capacity,
+            },
+            #[cfg(feature = "count-min")]
+            Counts::CountMin(sketch) => ModeBackend::CountMin {
+                width: sketch.width(),
+                depth: sketch.depth(),
+            },
+        }
+    }
+
+    pub(crate) fn is_degraded(&self) -> bool {
+        self.degraded
+    }
+
+    /// Counts `value` once with `weight`; `mean` is the mean including it,
+    /// which breaks ties for the mode.
+    pub(crate) fn add(&mut self, value: f64, weight: f64, mean: f64) {
+        self.add_count(value, 1, weight, mean);
+    }
+
+    /// Counts `value` `occurrences` times with a total of `weight`. A
+    /// count-min sketch only counts occurrences.
+    pub(crate) fn add_count(&mut self, value: f64, occurrences: usize, weight: f64, mean: f64) {
+        let added = Tally {
+            count: occurrences,
+            weight,
+        };
+        let (value, tally) = match &mut self.counts {
+            Counts::Exact(counts) => {
+                let tally = counts.entry(key(value)).or_default();
+                *tally += added;
+                (value, *tally)
+            }
+            Counts::Binned {
+                width,
+                origin,
+                bins,
+            } => {
+                let bin = bin(value, *width, *origin);
+                let tally = bins.entry(bin).or_default();
+                *tally += added;
+                (centre(bin, *width, *origin), *tally)
+            }
+            Counts::SpaceSaving { capacity, counters } => {
+                let key = key(value);
+                let mut inherited = Tally::default();
+                if !counters.contains_key(&key) && counters.len() >= *capacity {
+                    let (&evicted, &tally) = counters
+                        .iter()
+                        .min_by(|a, b| a.1.rank(b.1))
+                        .expect("capacity is at least one");
+                    counters.remove(&evicted);
+                    inherited = tally;
+                }
+                let tally = counters.entry(key).or_insert(inherited);
+                *tally += added;
+                (value, *tally)
+            }
+            #[cfg(feature = "count-min")]
+            Counts::CountMin(sketch) => {
+                sketch.add_count(value, occurrences);
+                if !self.recent.contains(&value) {
+                    if self.recent.len() >= SKETCH_CANDIDATES {
+                        self.recent.pop_front();
+                    }
+                    self.recent.push_back(value);
+                }
+                (value, Tally::occurrences(sketch.estimate(value)))
+            }
+        };
+        let closer = self
+            .leader
+            .is_none_or(|leader| (value - mean).abs() < (leader - mean).abs());
+        let rank = tally.rank(&self.max);
+        if rank.is_gt() || (rank.is_eq() && closer) {
+            self.max = tally;
+            self.leader = Some(value);
+        }
+        self.enforce_budget(mean);
+    }
+
+    /// Adds every count of `other`; bins of `other` count towards the value
+    /// at their centre.
+    pub(crate) fn merge(&mut self, other: &ModeTracker, mean: f64) {
+        for (value, tally) in other.entries() {
+            self.add_count(value, tally.count, tally.weight, mean);
+        }
+    }
+
+    /// Takes back one occurrence of `value` with a weight of one; values
+    /// that were never counted are ignored.
+    pub(crate) fn remove(&mut self, value: f64, mean: f64) {
+        let tally = match &mut self.counts {
+            Counts::Exact(counts) => take_one(counts, key(value)),
+            Counts::Binned {
+                width,
+                origin,
+                bins,
+            } => take_one(bins, bin(value, *width, *origin)),
+            Counts::SpaceSaving { counters, .. } => take_one(counters, key(value)),
+            #[cfg(feature = "count-min")]
+            Counts::CountMin(sketch) => {
+                let before = sketch.estimate(value);
+                sketch.remove(value);
+                Some(Tally::occurrences(before))
+            }
+        };
+        if tally == Some(self.max) {
+            self.refresh(mean);
+        }
+    }
+
+    /// Heaviest value, kept up to date on every add; of values tied for the
+    /// highest weight, the most frequent, and of those the one closest to
+    /// the mean when they tied.
+    pub(crate) fn mode(&self) -> Option<f64> {
+        self.leader
+    }
+
+    /// Every value tied with the mode, in ascending order, with its count.
+    pub(crate) fn modes(&self) -> Vec<(f64, usize)> {
+        let mut modes: Vec<(f64, usize)> = self
+            .entries()
+            .filter(|(_, tally)| self.max.count > 0 && tally.rank(&self.max).is_eq())
+            .map(|(value, tally)| (value, tally.count))
+            .collect();
+        modes.sort_by(|a, b| a.0.total_cmp(&b.0));
+        modes
+    }
+
+    /// Reference point for ties when no mean is at hand.
+    fn reference(&self) -> f64 {
+        self.leader.unwrap_or(0.0)
+    }
+
+    /// Finds the mode again after counts dropped or were restructured.
+    fn refresh(&mut self, mean: f64) {
+        self.max = self
+            .entries()
+            .map(|(_, tally)| tally)
+            .max_by(Tally::rank)
+            .unwrap_or_default();
+        let max = self.max;
+        self.leader = self
+            .entries()
+            .filter(|(_, tally)| max.count > 0 && tally.rank(&max).is_eq())
+            .map(|(value, _)| value)
+            .min_by(|a, b| {
+                (a - mean)
+                    .abs()
+                    .total_cmp(&(b - mean).abs())
+                    .then(a.total_cmp(b))
+            });
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (f64, Tally)> + '_> {
+        match &self.counts {
+            Counts::Exact(counts) => Box::new(
+                counts
+                    .iter()
+                    .map(|(bits, tally)| (f64::from_bits(*bits), *tally)),
+            ),
+            Counts::Binned {
+                width,
+                origin,
+                bins,
+            } => Box::new(
+                bins.iter()
+                    .map(move |(bin, tally)| (centre(*bin, *width, *origin), *tally)),
+            ),
+            Counts::SpaceSaving { counters, .. } => Box::new(
+                counters
+                    .iter()
+                    .map(|(bits, tally)| (f64::from_bits(*bits), *tally)),
+            ),
+            #[cfg(feature = "count-min")]
+            Counts::CountMin(sketch) => Box::new(
+                self.leader
+                    .filter(|leader| !self.recent.contains(leader))
+                    .into_iter()
+                    .chain(self.recent.iter().copied())
+                    .map(|value| (value, Tally::occurrences(sketch.estimate(value)))),
+            ),
+        }
+    }
+
+    /// How often `value` was counted: the count of its bin when binned, and
+    /// an estimate for the approximate backends.
+    pub(crate) fn frequency(&self, value: f64) -> usize {
+        match &self.counts {
+            Counts::Exact(counts) => counts.get(&key(value)).map_or(0, |tally| tally.count),
+            Counts::Binned {
+                width,
+                origin,
+                bins,
+            } => bins
+                .get(&bin(value, *width, *origin))
+                .map_or(0, |tally| tally.count),
+            Counts::SpaceSaving { counters, .. } => {
+                counters.get(&key(value)).map_or(0, |tally| tally.count)
+            }
+            #[cfg(feature = "count-min")]
+            Counts::CountMin(sketch) => sketch.estimate(value),
+        }
+    }
+
+    /// Every counted value with its count, bins at their centre. A sketch
+    /// only reports its recent values and the mode.
+    pub(crate) fn counts(&self) -> Vec<(f64, usize)> {
+        self.entries()
+            .map(|(value, tally)| (value, tally.count))
+            .collect()
+    }
+
+    /// Every counted value with its count and accumulated weight, as
+    /// [`counts`](Self::counts) lists them.
+    pub(crate) fn tallies(&self) -> Vec<(f64, usize, f64)> {
+        self.entries()
+            .map(|(value, tally)| (value, tally.count, tally.weight))
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        match &self.counts {
+            Counts::Exact(counts) => counts.len(),
+            Counts::Binned { bins, .. } => bins.len(),
+            Counts::SpaceSaving { counters, .. } => counters.len(),
+            #[cfg(feature = "count-min")]
+            Counts::CountMin(_) => self.recent.len(),
+        }
+    }
+
+    /// Coarsens the counts until they fit the memory budget: first from
+    /// exact values to bins spanning the observed range, then by doubling
+    /// the bin width. Space-saving counters are already bounded.
+    fn enforce_budget(&mut self, mean: f64) {
+        let Some(budget) = self.budget else {
+            return;
+        };
+        if !matches!(self.counts, Counts::Exact(_) | Counts::Binned { .. }) {
+            return;
+        }
+        let max_entries = (budget / ENTRY_BYTES).max(2);
+        if self.len() <= max_entries {
+            return;
+        }
+        while self.len() > max_entries {
+            self.degraded = true;
+            self.counts = match &self.counts {
+                Counts::Exact(counts) => {
+                    let values = counts.keys().map(|bits| f64::from_bits(*bits));
+                    let (min, max) = values
+                        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+                            (min.min(value), max.max(value))
+                        });
+                    let width = match (max - min) / (max_entries / 2) as f64 {
+                        width if width.is_finite() && width > 0.0 => width,
+                        _ => 1.0,
+                    };
+                    let mut bins = HashMap::new();
+                    for (bits, tally) in counts {
+                        *bins
+                            .entry(bin(f64::from_bits(*bits), width, 0.0))
+                            .or_default() += *tally;
+                    }
+                    Counts::Binned {
+                        width,
+                        origin: 0.0,
+                        bins,
+                    }
+                }
+                Counts::Binned {
+                    width,
+                    origin,
+                    bins,
+                } => {
+                    let mut coarser = HashMap::new();
+                    for (bin, tally) in bins {
+                        *coarser.entry(bin.div_euclid(2)).or_default() += *tally;
+                    }
+                    Counts::Binned {
+                        width: width * 2.0,
+                        origin: *origin,
+                        bins: coarser,
+                    }
+                }
+                _ => unreachable!("only exact and binned counts grow"),
+            };
+        }
+        self.refresh(mean);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_mode_ties_closest_to_mean() {
+        let mut high = ModeTracker::default();
+        let mut low = ModeTracker::default();
+        for value in [1.0, 1.0, 5.0, 5.0, 4.0] {
+            high.add(value, 1.0, 3.2);
+            low.add(value, 1.0, 2.0);
+        }
+        assert_eq!(high.mode(), Some(5.0));
+        assert_eq!(low.mode(), Some(1.0));
+        assert_eq!(ModeTracker::default().mode(), None);
+    }
+
+    #[test]
+    fn leader_follows_counts() {
+        let mut tracker = ModeTracker::default();
+        for value in [2.0, 9.0, 9.0, 2.0] {
+            tracker.add(value, 1.0, 8.0);
+        }
+        assert_eq!(tracker.mode(), Some(9.0));
+        assert_eq!(tracker.modes(), vec![(2.0, 2), (9.0, 2)]);
+        tracker.add(2.0, 1.0, 100.0);
+        assert_eq!(tracker.max.count, 3);
+        assert_eq!(tracker.mode(), Some(2.0));
+        assert_eq!(tracker.modes(), vec![(2.0, 3)]);
+    }
+
+    #[test]
+    fn flat_distribution_keeps_one_leader() {
+        let mut tracker = ModeTracker::default();
+        for value in 0..1000 {
+            tracker.add(value as f64, 1.0, 499.5);
+        }
+        assert_eq!(tracker.max.count, 1);
+        assert_eq!(tracker.mode(), Some(499.0));
+        assert_eq!(tracker.modes().len(), 1000);
+    }
+
+    #[test]
+    fn modes_lists_ties() {
+        let mut tracker = ModeTracker::default();
+        assert_eq!(tracker.modes(), vec![]);
+        for value in [5.0, 1.0, 5.0, 3.0, 1.0] {
+            tracker.add(value, 1.0, 0.0);
+        }
+        assert_eq!(tracker.modes(), vec![(1.0, 2), (5.0, 2)]);
+    }
+
+    #[test]
+    fn remove_updates_the_leader() {
+        let mut tracker = ModeTracker::default();
+        for value in [3.0, 3.0, 7.0] {
+            tracker.add(value, 1.0, 0.0);
+        }
+        tracker.remove(3.0, 6.0);
+        assert_eq!(tracker.max.count, 1);
+        assert_eq!(tracker.mode(), Some(7.0));
+        tracker.remove(3.0, 0.0);
+        tracker.remove(42.0, 0.0);
+        assert_eq!(tracker.len(), 1);
+        assert_eq!(tracker.mode(), Some(7.0));
+    }
+
+    #[test]
+    fn degrades_to_bins_over_budget() {
+        let mut tracker = ModeTracker::default();
+        tracker.set_budget(ENTRY_BYTES * 8);
+        for value in 0..100 {
+            tracker.add(value as f64, 1.0, 0.0);
+        }
+        tracker.add(42.0, 1.0, 0.0);
+        assert!(tracker.is_degraded());
+        assert!(tracker.len() <= 8);
+        let ModeBackend::Binned { width } = tracker.backend() else {
+            panic!("expected binned backend");
+        };
+        let mode = tracker.mode().unwrap();
+        assert!((mode - 42.0).abs() <= width);
+    }
+
+    #[test]
+    fn space_saving_keeps_frequent_values() {
+        let mut tracker = ModeTracker::default();
+        tracker.set_backend(ModeBackend::SpaceSaving { capacity: 4 });
+        for value in 0..1000 {
+            tracker.add(7.0, 1.0, 0.0);
+            tracker.add(value as f64, 1.0, 0.0);
+        }
+        assert_eq!(tracker.len(), 4);
+        assert_eq!(tracker.mode(), Some(7.0));
+        assert!(!tracker.is_degraded());
+        let cleared = tracker.cleared();
+        assert_eq!(cleared.backend(), ModeBackend::SpaceSaving { capacity: 4 });
+    }
+
+    #[test]
+    fn space_saving_inherits_evicted_count() {
+        let mut tracker = ModeTracker::default();
+        tracker.add(1.0, 1.0, 0.0);
+        tracker.add(1.0, 1.0, 0.0);
+        tracker.add(2.0, 1.0, 0.0);
+        tracker.set_backend(ModeBackend::SpaceSaving { capacity: 2 });
+        tracker.add(3.0, 1.0, 0.0);
+        let mut counts = tracker.counts();
+        counts.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert_eq!(counts, vec![(1.0, 2), (3.0, 2)]);
+    }
+
+    #[test]
+    fn frequency_per_backend() {
+        let mut tracker = ModeTracker::default();
+        for value in [1.0, 1.0, 1.4, 3.0] {
+            tracker.add(value, 1.0, 0.0);
+        }
+        assert_eq!(tracker.frequency(1.0), 2);
+        assert_eq!(tracker.frequency(2.0), 0);
+        tracker.set_backend(ModeBackend::Binned { width: 1.0 });
+        assert_eq!(tracker.frequency(1.9), 3);
+    }
+
+    #[test]
+    fn tolerance_groups_noisy_readings() {
+        let mut tracker = ModeTracker::default();
+        tracker.set_backend(ModeBackend::Tolerance { epsilon: 0.5 });
+        for value in [19.8, 20.1, 20.2, 19.9, 21.1, 20.6] {
+            tracker.add(value, 1.0, 0.0);
+        }
+        assert_eq!(tracker.mode(), Some(20.0));
+        assert_eq!(tracker.frequency(20.24), 4);
+        assert_eq!(tracker.backend(), ModeBackend::Tolerance { epsilon: 0.5 });
+        tracker.set_backend(ModeBackend::Tolerance { epsilon: 0.0 });
+        assert_eq!(tracker.backend(), ModeBackend::Exact);
+    }
+
+    #[test]
+    #[cfg(feature = "count-min")]
+    fn count_min_mode() {
+        let mut tracker = ModeTracker::default();
+        tracker.set_backend(ModeBackend::CountMin {
+            width: 512,
+            depth: 4,
+        });
+        for value in 0..5000 {
+            tracker.add(value as f64, 1.0, 0.0);
+            if value % 10 == 0 {
+                tracker.add(12.5, 1.0, 0.0);
+            }
+        }
+        assert_eq!(tracker.mode(), Some(12.5));
+        assert!(tracker.frequency(12.5) >= 500);
+        assert!(tracker.recent.len() <= SKETCH_CANDIDATES);
+        tracker.remove(12.5, 0.0);
+        assert_eq!(tracker.mode(), Some(12.5));
+    }
+
+    #[test]
+    fn cleared_keeps_budget() {
+        let mut tracker = ModeTracker::default();
+        tracker.set_budget(ENTRY_BYTES * 2);
+        for value in 0..10 {
+            tracker.add(value as f64, 1.0, 0.0);
+        }
+        let cleared = tracker.cleared();
+        assert_eq!(cleared.backend(), ModeBackend::Exact);
+        assert!(!cleared.is_degraded());
+        assert_eq!(cleared.budget, Some(ENTRY_BYTES * 2));
+    }
+}
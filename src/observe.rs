@@ -0,0 +1,75 @@
+use alloc::sync::Arc;
+
+use crate::{MovingError, MovingSnapshot};
+
+/// Notable transitions of a [`Moving`](crate::Moving), passed to the
+/// observer set with [`Moving::with_observer`](crate::Moving::with_observer).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MovingEvent {
+    /// The configured threshold became reached; the statistics after the
+    /// change.
+    ThresholdReached(MovingSnapshot),
+    /// The configured threshold stopped being reached.
+    ThresholdCleared(MovingSnapshot),
+    /// [`add_scored`](crate::Moving::add_scored) flagged a value as an
+    /// anomaly.
+    Anomaly { value: f64, z_score: f64 },
+    /// An ingestion stage dropped a value.
+    Rejected { value: f64, reason: MovingError },
+    /// The statistics were cleared; the statistics just before.
+    Reset(MovingSnapshot),
+}
+
+/// Callback receiving [`MovingEvent`]s.
+#[derive(Clone)]
+pub(crate) struct Observer(Arc<dyn Fn(&MovingEvent) + Send + Sync>);
+
+impl Observer {
+    pub(crate) fn new(observer: impl Fn(&MovingEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(observer))
+    }
+
+    pub(crate) fn notify(&self, event: MovingEvent) {
+        (self.0)(&event)
+    }
+}
+
+impl core::fmt::Debug for Observer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Observer(..)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{Moving, MovingError, MovingEvent, Threshold};
+
+    #[test]
+    fn reports_transitions() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&events);
+        let mut moving: Moving<f64> = Moving::new()
+            .with_valid_range(0.0, 100.0)
+            .with_threshold(Threshold::Upper(50.0))
+            .with_observer(move |event| log.lock().unwrap().push(*event));
+        moving.add(60.0);
+        moving.add(200.0);
+        moving.add(20.0);
+        moving.add(20.0);
+        moving.add_scored(80.0, 1.0).unwrap();
+        moving.reset();
+
+        let events = events.lock().unwrap();
+        assert!(matches!(events[0], MovingEvent::ThresholdReached(s) if s.mean == 60.0));
+        assert!(matches!(
+            events[1],
+            MovingEvent::Rejected { value, reason: MovingError::OutOfRange } if value == 200.0
+        ));
+        assert!(matches!(events[2], MovingEvent::ThresholdCleared(s) if s.count == 2));
+        assert!(matches!(events[3], MovingEvent::Anomaly { value, .. } if value == 80.0));
+        assert!(matches!(events[4], MovingEvent::Reset(s) if s.count == 4));
+        assert_eq!(events.len(), 5);
+    }
+}
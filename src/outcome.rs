@@ -0,0 +1,66 @@
+/// What an add through [`Moving::add_with_result`](crate::Moving::add_with_result)
+/// changed, for callers reacting to each value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AddOutcome {
+    /// The mean after the value.
+    pub mean: f64,
+    /// The count after the value.
+    pub count: usize,
+    /// Whether the mode moved to another value; always `false` without
+    /// mode tracking.
+    pub mode_changed: bool,
+    /// Standard deviations between the value as recorded, after any
+    /// transform stage, and the mean before it was added, see
+    /// [`Moving::z_score`](crate::Moving::z_score); `None` when the value
+    /// was skipped.
+    pub z_score: Option<f64>,
+    /// Whether this value brought the statistic back within the configured
+    /// threshold. While it is past the threshold the add returns
+    /// [`MovingError::ThresholdReached`](crate::MovingError::ThresholdReached)
+    /// instead.
+    pub threshold_cleared: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Moving, MovingError, NonFinitePolicy, Threshold};
+
+    #[test]
+    fn reports_threshold_clearing() {
+        let mut moving: Moving<f64> = Moving::new().with_threshold(Threshold::Upper(10.0));
+        let first = moving.add_with_result(4.0).unwrap();
+        assert_eq!((first.mean, first.count, first.z_score), (4.0, 1, None));
+        assert!(!first.mode_changed && !first.threshold_cleared);
+        assert!(matches!(
+            moving.add_with_result(30.0),
+            Err(MovingError::ThresholdReached(_))
+        ));
+        let cleared = moving.add_with_result(-10.0).unwrap();
+        assert!(cleared.threshold_cleared);
+        assert_eq!(cleared.mean, 8.0);
+        assert!(!moving.add_with_result(0.0).unwrap().threshold_cleared);
+    }
+
+    #[test]
+    fn skipped_value_does_not_clear_the_threshold() {
+        let mut moving: Moving<f64> = Moving::new()
+            .with_threshold(Threshold::Upper(10.0))
+            .with_non_finite_policy(NonFinitePolicy::Skip);
+        assert!(moving.add_with_result(30.0).is_err());
+        let skipped = moving.add_with_result(f64::NAN).unwrap();
+        assert!(!skipped.threshold_cleared);
+        assert!(moving.is_threshold_reached());
+    }
+
+    #[test]
+    fn z_score_is_for_the_recorded_value() {
+        let mut millis: Moving<f64> = Moving::new()
+            .with_transform(|seconds| seconds * 1000.0)
+            .with_non_finite_policy(NonFinitePolicy::Skip);
+        millis.add_slice(&[0.010, 0.020]);
+        let outcome = millis.add_with_result(0.030).unwrap();
+        assert!((outcome.z_score.unwrap() - 15.0 / 50f64.sqrt()).abs() < 1e-9);
+        assert_eq!(millis.add_with_result(f64::NAN).unwrap().z_score, None);
+        assert_eq!(millis.count(), 3);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{compat, MovingInput};
+
+/// Streaming covariance and Pearson correlation of `(x, y)` pairs, e.g.
+/// request size against latency, without keeping the pairs.
+///
+/// ```rust
+/// use moving_average::MovingPair;
+///
+/// let mut size_latency: MovingPair<u32, f64> = MovingPair::new();
+/// for (kilobytes, millis) in [(1, 10.0), (2, 19.0), (4, 41.0), (8, 80.0)] {
+///     size_latency.add(kilobytes, millis);
+/// }
+/// assert!(size_latency.correlation().unwrap() > 0.99);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MovingPair<X, Y = X> {
+    count: usize,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    co_moment: f64,
+    phantom: core::marker::PhantomData<(X, Y)>,
+}
+
+impl<X, Y> Default for MovingPair<X, Y> {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            mean_x: 0.0,
+            mean_y: 0.0,
+            m2_x: 0.0,
+            m2_y: 0.0,
+            co_moment: 0.0,
+            phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<X, Y> MovingPair<X, Y>
+where
+    X: MovingInput,
+    Y: MovingInput,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, x: X, y: Y) {
+        self.add_f64(x.to_f64(), y.to_f64());
+    }
+
+    pub(crate) fn add_f64(&mut self, x: f64, y: f64) {
+        self.count += 1;
+        let n = self.count as f64;
+        let delta_x = x - self.mean_x;
+        self.mean_x += delta_x / n;
+        let delta_y = y - self.mean_y;
+        self.mean_y += delta_y / n;
+        self.m2_x += delta_x * (x - self.mean_x);
+        self.m2_y += delta_y * (y - self.mean_y);
+        self.co_moment += delta_x * (y - self.mean_y);
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean_x(&self) -> f64 {
+        self.mean_x
+    }
+
+    pub fn mean_y(&self) -> f64 {
+        self.mean_y
+    }
+
+    /// Sample covariance; needs at least two pairs.
+    pub fn covariance(&self) -> Option<f64> {
+        (self.count > 1).then(|| self.co_moment / (self.count - 1) as f64)
+    }
+
+    /// Pearson correlation, from `-1.0` to `1.0`; `None` until both sides
+    /// vary.
+    pub fn correlation(&self) -> Option<f64> {
+        let spread = compat::sqrt(self.m2_x * self.m2_y);
+        (self.count > 1 && spread > 0.0).then(|| (self.co_moment / spread).clamp(-1.0, 1.0))
+    }
+
+    /// Slope of the least-squares line predicting `y` from `x`; `None`
+    /// until `x` varies.
+    pub fn slope(&self) -> Option<f64> {
+        (self.m2_x > 0.0).then(|| self.co_moment / self.m2_x)
+    }
+
+    /// Where the least-squares line crosses `x = 0`.
+    pub fn intercept(&self) -> Option<f64> {
+        Some(self.mean_y - self.slope()? * self.mean_x)
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn perfect_and_no_correlation() {
+        let mut pair: MovingPair<i32> = MovingPair::new();
+        assert_eq!(pair.covariance(), None);
+        for (x, y) in [(1, -2), (2, -4), (3, -6)] {
+            pair.add(x, y);
+        }
+        assert_eq!(pair.covariance(), Some(-2.0));
+        assert_eq!(pair.correlation(), Some(-1.0));
+        assert_eq!((pair.mean_x(), pair.mean_y()), (2.0, -4.0));
+        assert_eq!(pair.slope(), Some(-2.0));
+        assert_eq!(pair.intercept(), Some(0.0));
+
+        pair.reset();
+        for (x, y) in [(1, 5), (2, 5), (3, 5)] {
+            pair.add(x, y);
+        }
+        assert_eq!(pair.covariance(), Some(0.0));
+        assert_eq!(pair.correlation(), None);
+    }
+
+    #[test]
+    fn matches_two_pass_covariance() {
+        let xs = [2.1, 3.4, 1.9, 5.6, 4.2, 3.3];
+        let ys = [8.0, 9.5, 7.1, 13.2, 10.9, 9.0];
+        let mut pair: MovingPair<f64> = MovingPair::new();
+        for (x, y) in xs.iter().zip(ys) {
+            pair.add(*x, y);
+        }
+        let mean_x = xs.iter().sum::<f64>() / 6.0;
+        let mean_y = ys.iter().sum::<f64>() / 6.0;
+        let covariance: f64 = xs
+            .iter()
+            .zip(ys)
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum::<f64>()
+            / 5.0;
+        assert!((pair.covariance().unwrap() - covariance).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,94 @@
+use std::num::NonZeroUsize;
+use std::thread;
+
+use crate::{Moving, MovingInput};
+
+impl<T> Moving<T>
+where
+    T: MovingInput + Copy + Send + Sync,
+{
+    /// Averages `values` on every available core; see
+    /// [`par_extend_from_slice`](Self::par_extend_from_slice).
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let values: Vec<u32> = (1..=100_000).collect();
+    /// let moving = Moving::par_from_slice(&values);
+    /// assert_eq!(moving.count(), 100_000);
+    /// assert!((moving.mean() - 50_000.5).abs() < 1e-9);
+    /// ```
+    pub fn par_from_slice(values: &[T]) -> Self {
+        let mut moving = Self::new();
+        moving.par_extend_from_slice(values);
+        moving
+    }
+
+    /// Adds `values` by splitting them into one chunk per available core,
+    /// folding each chunk into a copy of this accumulator's configuration
+    /// on its own thread and [`merge`](Self::merge)-ing the results.
+    ///
+    /// The outcome matches adding the values one by one for the statistics
+    /// `merge` combines; order-dependent ones such as the last value and
+    /// quantile estimates only see the values added before.
+    pub fn par_extend_from_slice(&mut self, values: &[T]) {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let chunk = values.len().div_ceil(threads).max(1);
+        let mut template = self.clone();
+        template.observer = None;
+        template.reset();
+        let partials: Vec<Moving<T>> = thread::scope(|scope| {
+            let workers: Vec<_> = values
+                .chunks(chunk)
+                .map(|values| {
+                    let mut partial = template.clone();
+                    scope.spawn(move || {
+                        partial.add_iter(values.iter().copied());
+                        partial
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| {
+                    worker
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        });
+        for partial in &partials {
+            self.merge(partial);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_serial_accumulation() {
+        let values: Vec<i32> = (0..10_001).map(|i| (i * 37) % 101 - 50).collect();
+        let mut serial: Moving<i32> = Moving::new().with_mode_tracking();
+        serial.add_iter(values.iter().copied());
+        let mut parallel: Moving<i32> = Moving::new().with_mode_tracking();
+        parallel.par_extend_from_slice(&values);
+        assert_eq!(parallel.count(), serial.count());
+        assert!((parallel.mean() - serial.mean()).abs() < 1e-9);
+        assert!((parallel.variance().unwrap() - serial.variance().unwrap()).abs() < 1e-6);
+        assert_eq!(parallel.min(), serial.min());
+        assert_eq!(parallel.max(), serial.max());
+        assert_eq!(parallel.mode(), serial.mode());
+        assert_eq!(Moving::<u8>::par_from_slice(&[]).count(), 0);
+    }
+
+    #[test]
+    fn applies_ingestion_stages() {
+        let values = [1.0, 200.0, 3.0, -5.0];
+        let mut moving: Moving<f64> = Moving::new().with_valid_range(0.0, 100.0);
+        moving.par_extend_from_slice(&values);
+        assert_eq!(moving.count(), 2);
+        assert_eq!(moving.mean(), 2.0);
+    }
+}
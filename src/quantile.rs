@@ -0,0 +1,190 @@
+use crate::{compat, MovingInput};
+
+/// Streaming estimate of the `q` quantile in constant memory, using the P²
+/// algorithm of Jain and Chlamtac.
+///
+/// Five markers track the minimum, the maximum, the quantile itself and the
+/// points halfway to it, and are nudged along a piecewise-parabolic curve as
+/// samples arrive. Until five samples have been seen the quantile is exact.
+///
+/// ```rust
+/// use moving_average::Quantile;
+///
+/// let mut p95: Quantile<u32> = Quantile::new(0.95);
+/// for millis in 1..=1000 {
+///     p95.add(millis);
+/// }
+/// let estimate = p95.estimate().unwrap();
+/// assert!((estimate - 950.0).abs() < 10.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Quantile<T> {
+    q: f64,
+    count: usize,
+    heights: [f64; 5],
+    positions: [f64; 5],
+    desired: [f64; 5],
+    increments: [f64; 5],
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> Quantile<T>
+where
+    T: MovingInput,
+{
+    /// `q` is clamped to `0.0..=1.0`, e.g. `0.99` for the 99th percentile.
+    pub fn new(q: f64) -> Self {
+        let q = if q.is_nan() { 0.5 } else { q.clamp(0.0, 1.0) };
+        Self {
+            q,
+            count: 0,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * q, 1.0 + 4.0 * q, 3.0 + 2.0 * q, 5.0],
+            increments: [0.0, q / 2.0, q, (1.0 + q) / 2.0, 1.0],
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Adds a value; NaN values are ignored.
+    pub fn add(&mut self, value: T) {
+        self.add_f64(T::to_f64(value));
+    }
+
+    pub(crate) fn add_f64(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        if self.count < 5 {
+            self.heights[self.count] = value;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(f64::total_cmp);
+            }
+            return;
+        }
+        self.count += 1;
+
+        let cell = if value < self.heights[0] {
+            self.heights[0] = value;
+            0
+        } else if value >= self.heights[4] {
+            self.heights[4] = value;
+            3
+        } else {
+            (1..5).find(|i| value < self.heights[*i]).unwrap_or(4) - 1
+        };
+        for position in &mut self.positions[cell + 1..] {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(self.increments) {
+            *desired += increment;
+        }
+
+        for i in 1..4 {
+            let offset = self.desired[i] - self.positions[i];
+            let room_above = self.positions[i + 1] - self.positions[i];
+            let room_below = self.positions[i - 1] - self.positions[i];
+            if (offset >= 1.0 && room_above > 1.0) || (offset <= -1.0 && room_below < -1.0) {
+                let step = offset.signum();
+                let parabolic = self.parabolic(i, step);
+                self.heights[i] =
+                    if self.heights[i - 1] < parabolic && parabolic < self.heights[i + 1] {
+                        parabolic
+                    } else {
+                        self.linear(i, step)
+                    };
+                self.positions[i] += step;
+            }
+        }
+    }
+
+    fn parabolic(&self, i: usize, step: f64) -> f64 {
+        let (n, h) = (&self.positions, &self.heights);
+        h[i] + step / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + step) * (h[i + 1] - h[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - step) * (h[i] - h[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    fn linear(&self, i: usize, step: f64) -> f64 {
+        let j = if step > 0.0 { i + 1 } else { i - 1 };
+        self.heights[i]
+            + step * (self.heights[j] - self.heights[i]) / (self.positions[j] - self.positions[i])
+    }
+
+    /// Current estimate; exact, by linear interpolation between the closest
+    /// ranks, while fewer than five values have been added.
+    pub fn estimate(&self) -> Option<f64> {
+        match self.count {
+            0 => None,
+            1..=4 => {
+                let mut sorted = self.heights;
+                let sorted = &mut sorted[..self.count];
+                sorted.sort_by(f64::total_cmp);
+                let rank = self.q * (self.count - 1) as f64;
+                let (below, above) = (compat::floor(rank) as usize, compat::ceil(rank) as usize);
+                Some(sorted[below] + (sorted[above] - sorted[below]) * (rank - below as f64))
+            }
+            // The outer markers are the exact extremes.
+            _ if self.q == 0.0 => Some(self.heights[0]),
+            _ if self.q == 1.0 => Some(self.heights[4]),
+            _ => Some(self.heights[2]),
+        }
+    }
+
+    pub fn q(&self) -> f64 {
+        self.q
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.q);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_for_few_samples() {
+        let mut median: Quantile<f64> = Quantile::new(0.5);
+        assert_eq!(median.estimate(), None);
+        median.add(4.0);
+        median.add(1.0);
+        assert_eq!(median.estimate(), Some(2.5));
+        median.add(f64::NAN);
+        median.add(10.0);
+        assert_eq!(median.count(), 3);
+        assert_eq!(median.estimate(), Some(4.0));
+    }
+
+    #[test]
+    fn tracks_tail_quantiles() {
+        let mut p99: Quantile<u64> = Quantile::new(0.99);
+        let mut p50: Quantile<u64> = Quantile::new(0.5);
+        // A scrambled permutation of 0..10_000.
+        for i in 0..10_000u64 {
+            let value = i * 7_919 % 10_000;
+            p99.add(value);
+            p50.add(value);
+        }
+        assert!((p99.estimate().unwrap() - 9_900.0).abs() < 50.0);
+        assert!((p50.estimate().unwrap() - 5_000.0).abs() < 100.0);
+    }
+
+    #[test]
+    fn clamps_q() {
+        let mut max: Quantile<i32> = Quantile::new(2.0);
+        for value in [3, -1, 8, 5, 2, 7] {
+            max.add(value);
+        }
+        assert_eq!(max.q(), 1.0);
+        assert_eq!(max.estimate(), Some(8.0));
+        max.reset();
+        assert_eq!(max.count(), 0);
+    }
+}
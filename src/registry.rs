@@ -0,0 +1,177 @@
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use crate::{Moving, MovingInput, MovingSnapshot};
+
+/// One [`Moving`] per label, e.g. per endpoint or per host, created on the
+/// first value for that label from a shared configuration.
+///
+/// Series are kept ordered by label, so iteration and
+/// [`snapshots`](Self::snapshots) list them in a stable order.
+///
+/// ```rust
+/// use moving_average::{Moving, MovingRegistry};
+///
+/// let mut latency: MovingRegistry<String, u32> =
+///     MovingRegistry::with_template(Moving::new().with_mode_tracking());
+/// latency.add("GET /users", 12);
+/// latency.add("GET /users", 18);
+/// latency.add("POST /orders", 40);
+///
+/// assert_eq!(latency.len(), 2);
+/// assert_eq!(latency.get("GET /users").map(Moving::mean), Some(15.0));
+/// let labels: Vec<&str> = latency.iter().map(|(label, _)| label.as_str()).collect();
+/// assert_eq!(labels, ["GET /users", "POST /orders"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MovingRegistry<K, T> {
+    series: BTreeMap<K, Moving<T>>,
+    template: Moving<T>,
+}
+
+impl<K, T> Default for MovingRegistry<K, T>
+where
+    K: Ord,
+    T: MovingInput,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, T> MovingRegistry<K, T>
+where
+    K: Ord,
+    T: MovingInput,
+{
+    pub fn new() -> Self {
+        Self::with_template(Moving::new())
+    }
+
+    /// New series start as an empty copy of `template`, keeping its
+    /// configuration but none of its values.
+    pub fn with_template(template: Moving<T>) -> Self {
+        let mut template = template;
+        template.reset();
+        Self {
+            series: BTreeMap::new(),
+            template,
+        }
+    }
+
+    /// The configuration new series start from.
+    pub fn template(&self) -> &Moving<T> {
+        &self.template
+    }
+
+    /// Adds `value` to the series for `label`, creating it if needed.
+    pub fn add<Q>(&mut self, label: &Q, value: T)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ToOwned<Owned = K> + ?Sized,
+    {
+        self.series_mut(label).add(value);
+    }
+
+    /// The series for `label`, created if needed.
+    pub fn series_mut<Q>(&mut self, label: &Q) -> &mut Moving<T>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ToOwned<Owned = K> + ?Sized,
+    {
+        if !self.series.contains_key(label) {
+            self.series.insert(label.to_owned(), self.template.clone());
+        }
+        self.series
+            .get_mut(label)
+            .expect("series was just inserted")
+    }
+
+    pub fn get<Q>(&self, label: &Q) -> Option<&Moving<T>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.series.get(label)
+    }
+
+    pub fn remove<Q>(&mut self, label: &Q) -> Option<Moving<T>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.series.remove(label)
+    }
+
+    pub fn len(&self) -> usize {
+        self.series.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+
+    /// Every series, ordered by label.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &Moving<T>)> {
+        self.series.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut Moving<T>)> {
+        self.series.iter_mut()
+    }
+
+    /// The statistics of every series, ordered by label.
+    pub fn snapshots(&self) -> Vec<(&K, MovingSnapshot)> {
+        self.series
+            .iter()
+            .map(|(label, moving)| (label, moving.snapshot()))
+            .collect()
+    }
+
+    /// Resets every series, keeping the labels.
+    pub fn reset(&mut self) {
+        self.series.values_mut().for_each(Moving::reset);
+    }
+
+    /// Drops every series.
+    pub fn clear(&mut self) {
+        self.series.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn series_are_independent_and_lazily_created() {
+        let mut registry: MovingRegistry<u16, f64> = MovingRegistry::new();
+        assert!(registry.is_empty());
+        registry.add(&500, 3.0);
+        registry.add(&200, 1.0);
+        registry.add(&200, 2.0);
+        let snapshots = registry.snapshots();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!((*snapshots[0].0, snapshots[0].1.mean), (200, 1.5));
+        assert_eq!((*snapshots[1].0, snapshots[1].1.count), (500, 1));
+
+        registry.reset();
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.get(&200).map(Moving::count), Some(0));
+        assert_eq!(registry.remove(&500).map(|moving| moving.count()), Some(0));
+        registry.clear();
+        assert!(registry.get(&200).is_none());
+    }
+
+    #[test]
+    fn template_values_are_not_copied() {
+        let mut template: Moving<u8> = Moving::new().with_valid_range(0.0, 10.0);
+        template.add(5);
+        let mut registry: MovingRegistry<String, u8> = MovingRegistry::with_template(template);
+        registry.add("a", 50);
+        registry.add("a", 1);
+        assert_eq!(registry.get("a").map(Moving::count), Some(1));
+    }
+}
@@ -0,0 +1,181 @@
+use alloc::collections::BTreeMap;
+
+use crate::MovingInput;
+
+/// What one replica has contributed: how many values and their mean.
+///
+/// Plain data, so it can be sent between machines in whatever wire format
+/// the service already uses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplicaState {
+    pub count: u64,
+    pub mean: f64,
+}
+
+/// Mean that replicas on different machines can gossip and merge without
+/// coordination.
+///
+/// Each replica only ever updates its own entry and counts never decrease,
+/// so [`merge`](Self::merge) keeps the entry with the higher count per
+/// replica, and the higher mean should two diverged states share a count.
+/// Merging is commutative, associative and idempotent: replicas
+/// converge no matter how often or in which order states are exchanged.
+///
+/// ```rust
+/// use moving_average::ReplicatedMoving;
+///
+/// let mut a: ReplicatedMoving<u32> = ReplicatedMoving::new(1);
+/// let mut b: ReplicatedMoving<u32> = ReplicatedMoving::new(2);
+/// a.add(10);
+/// b.add(20);
+/// b.add(30);
+///
+/// a.merge(&b);
+/// b.merge(&a);
+/// b.merge(&a);
+/// assert_eq!(a.mean(), Some(20.0));
+/// assert_eq!(a.mean(), b.mean());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReplicatedMoving<T> {
+    replica: u64,
+    states: BTreeMap<u64, ReplicaState>,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> ReplicatedMoving<T>
+where
+    T: MovingInput,
+{
+    /// `replica` must be unique across the fleet.
+    pub fn new(replica: u64) -> Self {
+        Self {
+            replica,
+            states: BTreeMap::new(),
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    pub fn replica(&self) -> u64 {
+        self.replica
+    }
+
+    pub fn add(&mut self, value: T) {
+        let value = T::to_f64(value);
+        let state = self.states.entry(self.replica).or_insert(ReplicaState {
+            count: 0,
+            mean: 0.0,
+        });
+        state.count += 1;
+        state.mean += (value - state.mean) / state.count as f64;
+    }
+
+    /// Folds in everything `other` knows about.
+    pub fn merge(&mut self, other: &Self) {
+        for (replica, theirs) in &other.states {
+            self.merge_state(*replica, *theirs);
+        }
+    }
+
+    /// Folds in a single replica's state, e.g. one received over the wire.
+    pub fn merge_state(&mut self, replica: u64, state: ReplicaState) {
+        let ours = self.states.entry(replica).or_insert(state);
+        let newer = state
+            .count
+            .cmp(&ours.count)
+            .then_with(|| state.mean.total_cmp(&ours.mean));
+        if newer.is_gt() {
+            *ours = state;
+        }
+    }
+
+    /// Every replica's contribution known locally.
+    pub fn states(&self) -> impl Iterator<Item = (u64, ReplicaState)> + '_ {
+        self.states
+            .iter()
+            .map(|(replica, state)| (*replica, *state))
+    }
+
+    pub fn count(&self) -> u64 {
+        self.states.values().map(|state| state.count).sum()
+    }
+
+    /// Mean across every known replica.
+    pub fn mean(&self) -> Option<f64> {
+        let count = self.count();
+        (count > 0).then(|| {
+            self.states
+                .values()
+                .map(|state| state.mean * state.count as f64)
+                .sum::<f64>()
+                / count as f64
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replica(id: u64, values: &[f64]) -> ReplicatedMoving<f64> {
+        let mut replicated = ReplicatedMoving::new(id);
+        for value in values {
+            replicated.add(*value);
+        }
+        replicated
+    }
+
+    #[test]
+    fn merge_is_commutative_and_idempotent() {
+        let a = replica(1, &[1.0, 2.0, 3.0]);
+        let b = replica(2, &[10.0]);
+        let c = replica(3, &[4.0, 4.0]);
+
+        let mut left = a.clone();
+        left.merge(&b);
+        left.merge(&c);
+        left.merge(&b);
+
+        let mut right = c.clone();
+        right.merge(&a);
+        right.merge(&b);
+
+        assert_eq!(
+            left.states().collect::<Vec<_>>(),
+            right.states().collect::<Vec<_>>()
+        );
+        assert_eq!(left.count(), 6);
+        assert_eq!(left.mean(), Some(4.0));
+    }
+
+    #[test]
+    fn stale_state_does_not_regress() {
+        let mut a = replica(1, &[1.0]);
+        let stale = a.clone();
+        a.add(3.0);
+        a.merge(&stale);
+        assert_eq!(a.count(), 2);
+        assert_eq!(a.mean(), Some(2.0));
+
+        a.merge_state(
+            2,
+            ReplicaState {
+                count: 2,
+                mean: 8.0,
+            },
+        );
+        assert_eq!(a.mean(), Some(5.0));
+        assert_eq!(ReplicatedMoving::<f64>::new(9).mean(), None);
+    }
+
+    #[test]
+    fn equal_counts_merge_the_same_either_way() {
+        let mut left = replica(1, &[2.0]);
+        let mut right = replica(1, &[7.0]);
+        let (left_before, right_before) = (left.clone(), right.clone());
+        left.merge(&right_before);
+        right.merge(&left_before);
+        assert_eq!(left.mean(), Some(7.0));
+        assert_eq!(right.mean(), Some(7.0));
+    }
+}
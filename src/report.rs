@@ -0,0 +1,95 @@
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::{MovingInput, MovingSnapshot, SharedMoving};
+
+/// Background thread handing snapshots of a [`SharedMoving`] to a callback
+/// on a fixed cadence, from [`SharedMoving::report_every`]. Reporting stops
+/// when the reporter is dropped or [`stop`](Self::stop)ped.
+#[derive(Debug)]
+pub struct Reporter {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Reporter {
+    /// Stops reporting and waits for the reporting thread to finish.
+    pub fn stop(self) {}
+}
+
+impl Drop for Reporter {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl<T> SharedMoving<T>
+where
+    T: MovingInput + Send + Sync + 'static,
+{
+    /// Calls `report` with a snapshot every `interval`, e.g. to publish the
+    /// mean, count and mode as gauges to a metrics system.
+    ///
+    /// ```rust
+    /// use moving_average::{Moving, SharedMoving};
+    /// use std::sync::mpsc;
+    /// use std::time::Duration;
+    ///
+    /// let latency: SharedMoving<u32> = SharedMoving::new(Moving::new());
+    /// let (sender, gauges) = mpsc::channel();
+    /// let reporter = latency.report_every(Duration::from_millis(10), move |snapshot| {
+    ///     let _ = sender.send(snapshot.mean);
+    /// });
+    /// latency.add(40);
+    /// while gauges.recv().unwrap() != 40.0 {}
+    /// reporter.stop();
+    /// ```
+    pub fn report_every(
+        &self,
+        interval: Duration,
+        mut report: impl FnMut(MovingSnapshot) + Send + 'static,
+    ) -> Reporter {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let moving = self.clone();
+        let thread = std::thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                report(moving.snapshot());
+            }
+        });
+        Reporter {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn stops_when_dropped() {
+        let shared: SharedMoving<f64> = SharedMoving::default();
+        shared.add(2.0);
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let reporter = {
+            let reports = Arc::clone(&reports);
+            shared.report_every(Duration::from_millis(1), move |snapshot| {
+                reports.lock().unwrap().push(snapshot.count);
+            })
+        };
+        while reports.lock().unwrap().len() < 3 {
+            std::thread::yield_now();
+        }
+        drop(reporter);
+        let seen = reports.lock().unwrap().len();
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(reports.lock().unwrap().len(), seen);
+        assert!(reports.lock().unwrap().iter().all(|count| *count == 1));
+    }
+}
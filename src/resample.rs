@@ -0,0 +1,258 @@
+use alloc::vec::Vec;
+use std::time::{Duration, Instant};
+
+use crate::{GapPolicy, MovingInput};
+
+/// One fixed interval emitted by a [`Resampler`]. Filled intervals have a
+/// `count` of zero and the filled value as mean, minimum and maximum.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Resampled {
+    pub start: Instant,
+    pub count: usize,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Interval {
+    index: u64,
+    count: usize,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+/// Turns an irregular stream of timestamped values into one average per
+/// fixed interval, aligned to the first timestamp.
+///
+/// An interval is emitted once a value arrives in a later one, or on
+/// [`flush`](Self::flush). Values older than the open interval are
+/// counted in it. Empty intervals are filled from the neighbouring
+/// interval means per the [`GapPolicy`]; interpolated ones are emitted
+/// together with the interval after the gap.
+///
+/// ```rust
+/// use moving_average::{GapPolicy, Resampler};
+/// use std::time::{Duration, Instant};
+///
+/// let start = Instant::now();
+/// let second = Duration::from_secs(1);
+/// let mut resampler: Resampler<u32> = Resampler::new(second, GapPolicy::CarryForward);
+/// resampler.add_at(10, start);
+/// resampler.add_at(20, start + second / 2);
+/// resampler.add_at(40, start + second * 3);
+///
+/// let means: Vec<f64> = resampler
+///     .flush()
+///     .into_iter()
+///     .map(|interval| interval.mean)
+///     .collect();
+/// assert_eq!(means, [15.0, 15.0, 15.0, 40.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Resampler<T> {
+    interval: Duration,
+    policy: GapPolicy,
+    origin: Option<Instant>,
+    open: Option<Interval>,
+    ready: Vec<Resampled>,
+    previous: Option<f64>,
+    gap: Option<(u64, u64)>,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> Resampler<T>
+where
+    T: MovingInput,
+{
+    /// `interval` is raised to at least one nanosecond.
+    pub fn new(interval: Duration, policy: GapPolicy) -> Self {
+        Self {
+            interval: interval.max(Duration::from_nanos(1)),
+            policy,
+            origin: None,
+            open: None,
+            ready: Vec::new(),
+            previous: None,
+            gap: None,
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Adds a value observed now.
+    pub fn add(&mut self, value: T) {
+        self.add_at(value, Instant::now());
+    }
+
+    /// Adds a value observed at `at`, queueing the intervals it closes.
+    pub fn add_at(&mut self, value: T, at: Instant) {
+        let value = T::to_f64(value);
+        let origin = *self.origin.get_or_insert(at);
+        let index =
+            (at.saturating_duration_since(origin).as_nanos() / self.interval.as_nanos()) as u64;
+        let open = match self.open {
+            Some(open) if index > open.index => {
+                self.close(open);
+                self.fill(open.index + 1, index);
+                None
+            }
+            open => open,
+        };
+        let mut open = open.unwrap_or(Interval {
+            index,
+            count: 0,
+            sum: 0.0,
+            min: value,
+            max: value,
+        });
+        open.count += 1;
+        open.sum += value;
+        open.min = open.min.min(value);
+        open.max = open.max.max(value);
+        self.open = Some(open);
+    }
+
+    /// Takes the intervals closed so far, oldest first.
+    pub fn drain(&mut self) -> Vec<Resampled> {
+        core::mem::take(&mut self.ready)
+    }
+
+    /// Closes the open interval and takes every queued one.
+    pub fn flush(&mut self) -> Vec<Resampled> {
+        if let Some(open) = self.open.take() {
+            self.close(open);
+        }
+        self.drain()
+    }
+
+    fn start(&self, index: u64) -> Instant {
+        let nanos = self.interval.as_nanos() * u128::from(index);
+        let origin = self.origin.expect("intervals exist after the first value");
+        origin
+            + Duration::new(
+                (nanos / 1_000_000_000) as u64,
+                (nanos % 1_000_000_000) as u32,
+            )
+    }
+
+    fn close(&mut self, interval: Interval) {
+        let mean = interval.sum / interval.count as f64;
+        if let (Some((from, to)), Some(previous)) = (self.gap.take(), self.previous) {
+            let steps = (to - from + 1) as f64;
+            for index in from..to {
+                let filled = previous + (mean - previous) * (index - from + 1) as f64 / steps;
+                self.push_filled(index, filled);
+            }
+        }
+        self.ready.push(Resampled {
+            start: self.start(interval.index),
+            count: interval.count,
+            mean,
+            min: interval.min,
+            max: interval.max,
+        });
+        self.previous = Some(mean);
+    }
+
+    /// Emits intervals `from..to` per the gap policy, or holds them back
+    /// until the next interval closes when interpolating.
+    fn fill(&mut self, from: u64, to: u64) {
+        let value = match (self.policy, self.previous) {
+            (GapPolicy::Skip, _) | (_, None) => return,
+            (GapPolicy::Zero, _) => 0.0,
+            (GapPolicy::CarryForward, Some(previous)) => previous,
+            (GapPolicy::Interpolate, _) => {
+                self.gap = (from < to).then_some((from, to));
+                return;
+            }
+        };
+        for index in from..to {
+            self.push_filled(index, value);
+        }
+    }
+
+    fn push_filled(&mut self, index: u64, value: f64) {
+        self.ready.push(Resampled {
+            start: self.start(index),
+            count: 0,
+            mean: value,
+            min: value,
+            max: value,
+        });
+    }
+}
+
+/// Resamples timestamped values into intervals of `interval`; see
+/// [`Resampler`].
+pub fn resample<T: MovingInput>(
+    samples: impl IntoIterator<Item = (Instant, T)>,
+    interval: Duration,
+    policy: GapPolicy,
+) -> Vec<Resampled> {
+    let mut resampler = Resampler::new(interval, policy);
+    for (at, value) in samples {
+        resampler.add_at(value, at);
+    }
+    resampler.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gap_policies() {
+        let start = Instant::now();
+        let second = Duration::from_secs(1);
+        let samples = [(start, 4.0), (start + second * 3, 8.0), (start, 2.0)];
+        let means = |policy| {
+            resample(samples, second, policy)
+                .into_iter()
+                .map(|interval| (interval.count, interval.mean))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(means(GapPolicy::Skip), [(1, 4.0), (2, 5.0)]);
+        assert_eq!(
+            means(GapPolicy::Zero),
+            [(1, 4.0), (0, 0.0), (0, 0.0), (2, 5.0)]
+        );
+        assert_eq!(
+            means(GapPolicy::CarryForward),
+            [(1, 4.0), (0, 4.0), (0, 4.0), (2, 5.0)]
+        );
+        assert_eq!(
+            means(GapPolicy::Interpolate),
+            [
+                (1, 4.0),
+                (0, 4.0 + 1.0 / 3.0),
+                (0, 4.0 + 2.0 / 3.0),
+                (2, 5.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn intervals_are_aligned_to_the_first_value() {
+        let start = Instant::now();
+        let mut resampler: Resampler<i32> =
+            Resampler::new(Duration::from_millis(100), GapPolicy::Skip);
+        resampler.add_at(-1, start);
+        resampler.add_at(3, start + Duration::from_millis(99));
+        assert!(resampler.drain().is_empty());
+        resampler.add_at(7, start + Duration::from_millis(250));
+        let closed = resampler.drain();
+        assert_eq!(closed.len(), 1);
+        assert_eq!(
+            (closed[0].start, closed[0].min, closed[0].max),
+            (start, -1.0, 3.0)
+        );
+        let open = resampler.flush();
+        assert_eq!(open[0].start, start + Duration::from_millis(200));
+        assert!(resampler.flush().is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+use alloc::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::{Moving, MovingInput};
+
+/// Bucket size of a [`RollUp`] level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Granularity {
+    Minute,
+    Hour,
+    Day,
+}
+
+impl Granularity {
+    const ALL: [Granularity; 3] = [Granularity::Minute, Granularity::Hour, Granularity::Day];
+
+    pub fn span(self) -> Duration {
+        Duration::from_secs(match self {
+            Granularity::Minute => 60,
+            Granularity::Hour => 60 * 60,
+            Granularity::Day => 24 * 60 * 60,
+        })
+    }
+
+    fn level(self) -> usize {
+        self as usize
+    }
+}
+
+/// Statistics over one closed minute, hour or day of a [`RollUp`].
+#[derive(Debug, Clone)]
+pub struct RollUpBucket<T> {
+    start: Instant,
+    moving: Moving<T>,
+}
+
+impl<T> RollUpBucket<T>
+where
+    T: MovingInput,
+{
+    pub fn start(&self) -> Instant {
+        self.start
+    }
+
+    pub fn count(&self) -> usize {
+        self.moving.count()
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.moving.mean()
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.moving.min()
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.moving.max()
+    }
+
+    pub fn moving(&self) -> &Moving<T> {
+        &self.moving
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Level<T> {
+    open: Option<(u64, Moving<T>)>,
+    closed: VecDeque<RollUpBucket<T>>,
+    retention: usize,
+}
+
+/// Per-minute, per-hour and per-day statistics from one ingest path.
+///
+/// Values go into the open minute; when a value arrives in a later minute
+/// the open one is closed, kept among the recent minutes and folded into
+/// the open hour, and likewise from hours into days. Buckets are aligned
+/// to the first value's timestamp and periods without values leave no
+/// bucket. Each granularity keeps a bounded number of closed buckets,
+/// by default 60 minutes, 24 hours and 30 days.
+///
+/// ```rust
+/// use moving_average::{Granularity, RollUp};
+/// use std::time::{Duration, Instant};
+///
+/// let start = Instant::now();
+/// let mut latency: RollUp<u32> = RollUp::new();
+/// for minute in 0..90 {
+///     let value = if minute < 60 { 100 } else { 40 };
+///     latency.add_at(value, start + Duration::from_secs(minute * 60));
+/// }
+///
+/// assert_eq!(latency.recent(Granularity::Minute, 29).mean(), 40.0);
+/// assert_eq!(latency.current(Granularity::Day).mean(), 80.0);
+/// assert_eq!(latency.buckets(Granularity::Hour).count(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RollUp<T> {
+    levels: [Level<T>; 3],
+    origin: Option<Instant>,
+    latest: Option<Instant>,
+    template: Moving<T>,
+}
+
+impl<T> Default for RollUp<T>
+where
+    T: MovingInput,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> RollUp<T>
+where
+    T: MovingInput,
+{
+    pub fn new() -> Self {
+        Self::with_template(Moving::new())
+    }
+
+    /// Every bucket starts as an empty copy of `template`, e.g. to track
+    /// the mode per bucket as well.
+    pub fn with_template(template: Moving<T>) -> Self {
+        let mut template = template;
+        template.reset();
+        let level = |retention| Level {
+            open: None,
+            closed: VecDeque::new(),
+            retention,
+        };
+        Self {
+            levels: [level(60), level(24), level(30)],
+            origin: None,
+            latest: None,
+            template,
+        }
+    }
+
+    /// Keeps the `buckets` most recent closed buckets of `granularity`.
+    pub fn with_retention(mut self, granularity: Granularity, buckets: usize) -> Self {
+        let level = &mut self.levels[granularity.level()];
+        level.retention = buckets;
+        level.closed.truncate(buckets);
+        self
+    }
+
+    /// Adds a value observed now.
+    pub fn add(&mut self, value: T) {
+        self.add_at(value, Instant::now());
+    }
+
+    /// Adds a value observed at `at`. Values older than the newest
+    /// timestamp seen go into the open buckets.
+    pub fn add_at(&mut self, value: T, at: Instant) {
+        self.roll(at);
+        if let Some((_, moving)) = &mut self.levels[0].open {
+            moving.add(value);
+        }
+    }
+
+    /// Closes the buckets that ended before `now`, e.g. to account for a
+    /// quiet period before reading the statistics.
+    pub fn roll(&mut self, now: Instant) {
+        let origin = *self.origin.get_or_insert(now);
+        let now = self.latest.map_or(now, |latest| latest.max(now));
+        self.latest = Some(now);
+        let elapsed = now.saturating_duration_since(origin);
+        for granularity in Granularity::ALL {
+            let index = elapsed.as_secs() / granularity.span().as_secs();
+            let level = granularity.level();
+            match &self.levels[level].open {
+                Some((open, _)) if *open == index => continue,
+                Some(_) => self.close(granularity),
+                None => {}
+            }
+            self.levels[level].open = Some((index, self.template.clone()));
+        }
+    }
+
+    fn close(&mut self, granularity: Granularity) {
+        let level = granularity.level();
+        let Some((index, moving)) = self.levels[level].open.take() else {
+            return;
+        };
+        if let Some((_, parent)) = self
+            .levels
+            .get_mut(level + 1)
+            .and_then(|parent| parent.open.as_mut())
+        {
+            parent.merge(&moving);
+        }
+        let level = &mut self.levels[level];
+        if level.retention == 0 {
+            return;
+        }
+        let origin = self.origin.expect("buckets exist after the first value");
+        level.closed.push_front(RollUpBucket {
+            start: origin + Duration::from_secs(granularity.span().as_secs() * index),
+            moving,
+        });
+        level.closed.truncate(level.retention);
+    }
+
+    /// Statistics over the open bucket of `granularity`, including the
+    /// values still in the open buckets below it.
+    pub fn current(&self, granularity: Granularity) -> Moving<T> {
+        let mut current = self.template.clone();
+        for level in &self.levels[..=granularity.level()] {
+            if let Some((_, moving)) = &level.open {
+                current.merge(moving);
+            }
+        }
+        current
+    }
+
+    /// Statistics over the open bucket of `granularity` and up to
+    /// `closed` of the most recent closed ones, e.g. the last hour as the
+    /// current minute and the 59 before it.
+    pub fn recent(&self, granularity: Granularity, closed: usize) -> Moving<T> {
+        let mut recent = self.current(granularity);
+        for bucket in self.buckets(granularity).take(closed) {
+            recent.merge(&bucket.moving);
+        }
+        recent
+    }
+
+    /// The retained closed buckets of `granularity`, newest first.
+    pub fn buckets(&self, granularity: Granularity) -> impl Iterator<Item = &RollUpBucket<T>> {
+        self.levels[granularity.level()].closed.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cascades_closed_buckets() {
+        let start = Instant::now();
+        let mut rollup: RollUp<f64> = RollUp::new().with_retention(Granularity::Minute, 2);
+        let minute = Duration::from_secs(60);
+        rollup.add_at(1.0, start);
+        rollup.add_at(3.0, start + minute / 2);
+        rollup.add_at(10.0, start + minute * 5);
+        // Late values join the open bucket.
+        rollup.add_at(20.0, start);
+        rollup.add_at(0.0, start + minute * 61);
+
+        let minutes: Vec<_> = rollup
+            .buckets(Granularity::Minute)
+            .map(|bucket| (bucket.start(), bucket.count(), bucket.mean()))
+            .collect();
+        assert_eq!(minutes, [(start + minute * 5, 2, 15.0), (start, 2, 2.0)]);
+        let hours: Vec<_> = rollup.buckets(Granularity::Hour).collect();
+        assert_eq!(hours.len(), 1);
+        assert_eq!(
+            (hours[0].count(), hours[0].min(), hours[0].max()),
+            (4, Some(1.0), Some(20.0))
+        );
+        assert_eq!(rollup.current(Granularity::Hour).count(), 1);
+        assert_eq!(rollup.current(Granularity::Day).count(), 5);
+        assert_eq!(rollup.buckets(Granularity::Day).count(), 0);
+    }
+
+    #[test]
+    fn roll_closes_idle_buckets() {
+        let start = Instant::now();
+        let mut rollup: RollUp<u8> = RollUp::new().with_retention(Granularity::Hour, 0);
+        rollup.add_at(4, start);
+        rollup.roll(start + Duration::from_secs(7200));
+        assert_eq!(rollup.current(Granularity::Minute).count(), 0);
+        assert_eq!(rollup.recent(Granularity::Minute, 1).mean(), 4.0);
+        assert_eq!(rollup.buckets(Granularity::Hour).count(), 0);
+        assert_eq!(rollup.current(Granularity::Day).count(), 1);
+    }
+}
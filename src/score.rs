@@ -0,0 +1,110 @@
+use crate::{Moving, MovingError, MovingEvent, MovingInput};
+
+/// How unusual an added value was, from [`Moving::add_scored`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Score {
+    /// The mean after the value was added.
+    pub mean: f64,
+    /// Standard deviations between the value as recorded, after any
+    /// transform stage, and the mean before it was added; `None` until two
+    /// values with some spread were seen, and when the value was skipped.
+    pub z_score: Option<f64>,
+    /// Whether the z-score is beyond the given number of standard
+    /// deviations.
+    pub anomaly: bool,
+}
+
+impl<T> Moving<T>
+where
+    T: MovingInput,
+{
+    /// Standard deviations between `value` and the current mean; `None`
+    /// until two values with some spread were added.
+    pub fn z_score(&self, value: f64) -> Option<f64> {
+        let std_dev = self.std_dev().filter(|std_dev| *std_dev > 0.0)?;
+        Some((value - self.mean()) / std_dev)
+    }
+
+    /// Adds a value like [`add_with_result`](Self::add_with_result), scoring
+    /// the value about to be recorded against the statistics so far. A value
+    /// more than `sigma` standard deviations from the mean is flagged as an
+    /// anomaly; it is still added. Values rejected by the ingestion stages
+    /// return the error without being scored.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut latency: Moving<u32> = Moving::new();
+    /// for millis in [10, 12, 11, 9, 10, 12, 8] {
+    ///     latency.add(millis);
+    /// }
+    /// let score = latency.add_scored(11, 3.0).unwrap();
+    /// assert!(!score.anomaly);
+    /// let score = latency.add_scored(95, 3.0).unwrap();
+    /// assert!(score.anomaly);
+    /// assert!(score.z_score.unwrap() > 3.0);
+    /// ```
+    pub fn add_scored(&mut self, value: T, sigma: f64) -> Result<Score, MovingError> {
+        let Some(value) = self.admit(T::to_f64(value))? else {
+            return Ok(Score {
+                mean: self.mean(),
+                z_score: None,
+                anomaly: false,
+            });
+        };
+        let z_score = self.z_score(value);
+        let anomaly = z_score.filter(|z_score| z_score.abs() > sigma);
+        if let Some(z_score) = anomaly {
+            self.notify(MovingEvent::Anomaly { value, z_score });
+        }
+        self.record(value, 1.0, None);
+        self.check_threshold(value)?;
+        Ok(Score {
+            mean: self.mean(),
+            z_score,
+            anomaly: anomaly.is_some(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_against_previous_values() {
+        let mut moving_average: Moving<f64> = Moving::new();
+        let first = moving_average.add_scored(5.0, 1.0).unwrap();
+        assert_eq!(first.z_score, None);
+        assert!(!first.anomaly);
+        moving_average.add(5.0);
+        assert_eq!(moving_average.z_score(9.0), None);
+        moving_average.add(7.0);
+        // Mean 17/3, sample standard deviation sqrt(4/3).
+        let score = moving_average.add_scored(3.0, 2.0).unwrap();
+        assert!((score.z_score.unwrap() + (8.0 / 3.0) / (4.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert!(score.anomaly);
+        assert_eq!(score.mean, 5.0);
+    }
+
+    #[test]
+    fn scores_the_recorded_value() {
+        use crate::NonFinitePolicy;
+
+        let mut celsius: Moving<f64> = Moving::new()
+            .with_transform(|fahrenheit| (fahrenheit - 32.0) * 5.0 / 9.0)
+            .with_valid_range(-50.0, 60.0)
+            .with_non_finite_policy(NonFinitePolicy::Skip);
+        celsius.add_slice(&[50.0, 68.0]);
+        // 86 F is 30 C, 15 C above the mean with a standard deviation of
+        // sqrt(50) C; scoring the raw 86 would flag it.
+        let score = celsius.add_scored(86.0, 3.0).unwrap();
+        assert!((score.z_score.unwrap() - 15.0 / 50f64.sqrt()).abs() < 1e-12);
+        assert!(!score.anomaly);
+        let skipped = celsius.add_scored(f64::NAN, 3.0).unwrap();
+        assert_eq!(skipped.z_score, None);
+        assert_eq!(celsius.count(), 3);
+        assert!(celsius.add_scored(1000.0, 3.0).is_err());
+        assert_eq!(celsius.count(), 3);
+    }
+}
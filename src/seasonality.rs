@@ -0,0 +1,179 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::sparkline::sparkline;
+use crate::window::Window;
+use crate::MovingInput;
+
+const DEFAULT_MIN_CORRELATION: f64 = 0.3;
+
+/// Detects the dominant period of a stream from the autocorrelation of its
+/// most recent samples.
+///
+/// ```rust
+/// use moving_average::Seasonality;
+///
+/// let mut seasonality: Seasonality<f64> = Seasonality::new(48);
+/// for i in 0..48 {
+///     seasonality.add([1.0, 5.0, 3.0, 0.0][i % 4]);
+/// }
+/// assert_eq!(seasonality.period(), Some(4));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Seasonality<T> {
+    window: Window,
+    max_period: Option<usize>,
+    min_correlation: f64,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> Seasonality<T>
+where
+    T: MovingInput,
+{
+    /// Retains the last `window` samples and searches periods up to half of it.
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: Window::new(window),
+            max_period: None,
+            min_correlation: DEFAULT_MIN_CORRELATION,
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    pub fn with_max_period(mut self, max_period: usize) -> Self {
+        self.max_period = Some(max_period);
+        self
+    }
+
+    /// Minimum autocorrelation a lag needs before it is reported as a period.
+    pub fn with_min_correlation(mut self, min_correlation: f64) -> Self {
+        self.min_correlation = min_correlation;
+        self
+    }
+
+    pub fn add(&mut self, value: T) {
+        self.window.push(T::to_f64(value));
+    }
+
+    /// Resizes the retained window, dropping the oldest samples when it
+    /// shrinks below the number currently held.
+    pub fn set_window(&mut self, window: usize) {
+        self.window.set_capacity(window);
+    }
+
+    pub fn window(&self) -> usize {
+        self.window.capacity()
+    }
+
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+
+    /// Unicode sparkline of the retained samples, at most `width` characters.
+    pub fn sparkline(&self, width: usize) -> String {
+        sparkline(self.window.iter(), width)
+    }
+
+    /// Autocorrelation of the retained samples at `lag`.
+    pub fn autocorrelation(&self, lag: usize) -> Option<f64> {
+        let n = self.window.len();
+        if lag == 0 || lag >= n {
+            return None;
+        }
+        let mean = self.window.iter().sum::<f64>() / n as f64;
+        let denominator: f64 = self.window.iter().map(|x| (x - mean) * (x - mean)).sum();
+        if denominator == 0.0 {
+            return None;
+        }
+        let numerator: f64 = self
+            .window
+            .iter()
+            .zip(self.window.iter().skip(lag))
+            .map(|(a, b)| (a - mean) * (b - mean))
+            .sum();
+        Some(numerator / denominator)
+    }
+
+    /// The lag with the strongest autocorrelation peak, if any clears the
+    /// minimum correlation.
+    pub fn period(&self) -> Option<usize> {
+        let max_lag = self
+            .max_period
+            .unwrap_or(usize::MAX)
+            .min(self.window.len() / 2);
+        if max_lag < 2 {
+            return None;
+        }
+        let correlations: Vec<f64> = (1..=max_lag + 1)
+            .map(|lag| self.autocorrelation(lag).unwrap_or(f64::NEG_INFINITY))
+            .collect();
+
+        (2..=max_lag)
+            .filter(|&lag| {
+                let r = correlations[lag - 1];
+                r >= self.min_correlation && r > correlations[lag - 2] && r >= correlations[lag]
+            })
+            .max_by(|&a, &b| correlations[a - 1].total_cmp(&correlations[b - 1]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_sine_period() {
+        let mut seasonality: Seasonality<f64> = Seasonality::new(120);
+        for i in 0..120 {
+            seasonality.add((i as f64 * core::f64::consts::TAU / 12.0).sin());
+        }
+        assert_eq!(seasonality.period(), Some(12));
+    }
+
+    #[test]
+    fn no_period_for_constant_or_short_streams() {
+        let mut seasonality: Seasonality<usize> = Seasonality::new(20);
+        for _ in 0..20 {
+            seasonality.add(7);
+        }
+        assert_eq!(seasonality.period(), None);
+
+        let mut seasonality: Seasonality<usize> = Seasonality::new(20);
+        seasonality.add(1);
+        seasonality.add(2);
+        assert_eq!(seasonality.period(), None);
+    }
+
+    #[test]
+    fn resizing_keeps_recent_samples() {
+        let mut seasonality: Seasonality<f64> = Seasonality::new(24);
+        for i in 0..24 {
+            seasonality.add([1.0, 5.0, 3.0][i % 3]);
+        }
+        seasonality.set_window(12);
+        assert_eq!(seasonality.window(), 12);
+        assert_eq!(seasonality.len(), 12);
+        assert_eq!(seasonality.period(), Some(3));
+
+        seasonality.set_window(48);
+        assert_eq!(seasonality.len(), 12);
+        for i in 0..36 {
+            seasonality.add([1.0, 5.0, 3.0][i % 3]);
+        }
+        assert_eq!(seasonality.len(), 48);
+    }
+
+    #[test]
+    fn respects_max_period() {
+        let mut seasonality: Seasonality<f64> = Seasonality::new(120).with_max_period(8);
+        for i in 0..120 {
+            seasonality.add((i as f64 * core::f64::consts::TAU / 12.0).sin());
+        }
+        assert_eq!(seasonality.period(), None);
+    }
+}
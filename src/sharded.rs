@@ -0,0 +1,117 @@
+use std::hash::{BuildHasher, RandomState};
+use std::sync::{Mutex, PoisonError};
+
+use crate::{Moving, MovingInput};
+
+/// Accumulator split into independently locked shards, so threads adding
+/// values on a hot path rarely contend on the same lock.
+///
+/// Each thread adds to the shard its id hashes to;
+/// [`aggregate`](Self::aggregate) merges the shards into a global view and
+/// [`flush`](Self::flush) also empties them, e.g. once per reporting
+/// interval.
+///
+/// ```rust
+/// use moving_average::{Moving, ShardedMoving};
+/// use std::sync::Arc;
+///
+/// let requests: Arc<ShardedMoving<u32>> = Arc::new(ShardedMoving::new(Moving::new(), 4));
+/// let workers: Vec<_> = (1..=8)
+///     .map(|worker| {
+///         let requests = Arc::clone(&requests);
+///         std::thread::spawn(move || requests.add(worker))
+///     })
+///     .collect();
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
+/// let interval = requests.flush();
+/// assert_eq!(interval.count(), 8);
+/// assert!((interval.mean() - 4.5).abs() < 1e-12);
+/// assert_eq!(requests.aggregate().count(), 0);
+/// ```
+#[derive(Debug)]
+pub struct ShardedMoving<T> {
+    shards: Vec<Mutex<Moving<T>>>,
+    template: Moving<T>,
+    hasher: RandomState,
+}
+
+impl<T> ShardedMoving<T>
+where
+    T: MovingInput,
+{
+    /// `shards` copies of `moving`'s configuration, at least one. The
+    /// statistics already in `moving` go into the first shard.
+    pub fn new(moving: Moving<T>, shards: usize) -> Self {
+        let mut template = moving.clone();
+        template.observer = None;
+        template.reset();
+        let mut shards: Vec<_> = (0..shards.max(1))
+            .map(|_| Mutex::new(template.clone()))
+            .collect();
+        shards[0] = Mutex::new(moving);
+        Self {
+            shards,
+            template,
+            hasher: RandomState::new(),
+        }
+    }
+
+    pub fn shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Adds `value` to the calling thread's shard.
+    pub fn add(&self, value: T) {
+        let shard = self.hasher.hash_one(std::thread::current().id()) as usize % self.shards.len();
+        self.shards[shard]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .add(value);
+    }
+
+    /// Every shard merged into one accumulator.
+    pub fn aggregate(&self) -> Moving<T> {
+        self.collect(false)
+    }
+
+    /// Like [`aggregate`](Self::aggregate), emptying each shard as it is
+    /// merged so the next flush only covers later values.
+    pub fn flush(&self) -> Moving<T> {
+        self.collect(true)
+    }
+
+    fn collect(&self, reset: bool) -> Moving<T> {
+        let mut total = self.template.clone();
+        for shard in &self.shards {
+            let mut shard = shard.lock().unwrap_or_else(PoisonError::into_inner);
+            total.merge(&shard);
+            if reset {
+                shard.reset();
+            }
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_configuration_and_initial_values() {
+        let mut initial: Moving<f64> = Moving::new().with_mode_tracking();
+        initial.add(1.0);
+        let sharded = ShardedMoving::new(initial, 0);
+        assert_eq!(sharded.shards(), 1);
+        sharded.add(3.0);
+        sharded.add(3.0);
+        let total = sharded.aggregate();
+        assert_eq!(total.mode(), Some(3.0));
+        assert_eq!(total.count(), 3);
+        assert_eq!(sharded.flush().count(), 3);
+        sharded.add(5.0);
+        assert_eq!(sharded.flush().mean(), 5.0);
+    }
+}
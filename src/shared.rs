@@ -0,0 +1,125 @@
+use std::sync::{Arc, PoisonError, RwLock};
+
+use crate::{AddOutcome, Moving, MovingError, MovingInput, MovingSnapshot};
+
+/// Cloneable handle to one [`Moving`] behind a lock, so producer threads
+/// can add values while a reporter reads the statistics.
+///
+/// Every clone refers to the same accumulator. Adds take the write lock
+/// for the duration of one update; reads take the read lock. A panic while
+/// the lock is held does not poison the handle, as every update leaves the
+/// accumulator consistent.
+///
+/// ```rust
+/// use moving_average::{Moving, SharedMoving};
+///
+/// let latency: SharedMoving<u32> = SharedMoving::new(Moving::new().with_mode_tracking());
+/// let producers: Vec<_> = (0..4)
+///     .map(|worker| {
+///         let latency = latency.clone();
+///         std::thread::spawn(move || latency.add(10 * worker))
+///     })
+///     .collect();
+/// for producer in producers {
+///     producer.join().unwrap();
+/// }
+/// assert_eq!(latency.count(), 4);
+/// assert_eq!(latency.mean(), 15.0);
+/// ```
+#[derive(Debug)]
+pub struct SharedMoving<T> {
+    inner: Arc<RwLock<Moving<T>>>,
+}
+
+impl<T> Clone for SharedMoving<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> Default for SharedMoving<T>
+where
+    T: MovingInput,
+{
+    fn default() -> Self {
+        Self::new(Moving::new())
+    }
+}
+
+impl<T> From<Moving<T>> for SharedMoving<T> {
+    fn from(moving: Moving<T>) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(moving)),
+        }
+    }
+}
+
+impl<T> SharedMoving<T>
+where
+    T: MovingInput,
+{
+    /// Shares `moving`, keeping its configuration.
+    pub fn new(moving: Moving<T>) -> Self {
+        Self::from(moving)
+    }
+
+    pub fn add(&self, value: T) {
+        self.write(|moving| moving.add(value));
+    }
+
+    pub fn add_with_result(&self, value: T) -> Result<AddOutcome, MovingError> {
+        self.write(|moving| moving.add_with_result(value))
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.read(Moving::mean)
+    }
+
+    pub fn count(&self) -> usize {
+        self.read(Moving::count)
+    }
+
+    /// The current statistics, taken under a single read lock.
+    pub fn snapshot(&self) -> MovingSnapshot {
+        self.read(Moving::snapshot)
+    }
+
+    /// Runs `f` with shared access to the accumulator.
+    pub fn read<R>(&self, f: impl FnOnce(&Moving<T>) -> R) -> R {
+        f(&self.inner.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Runs `f` with exclusive access to the accumulator, e.g. to remove a
+    /// value or reset it.
+    pub fn write<R>(&self, f: impl FnOnce(&mut Moving<T>) -> R) -> R {
+        f(&mut self.inner.write().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handles_share_one_accumulator() {
+        let shared: SharedMoving<f64> = SharedMoving::default();
+        let handle = shared.clone();
+        handle.add(3.0);
+        shared.add(5.0);
+        assert_eq!(handle.mean(), 4.0);
+        shared.write(Moving::reset);
+        assert_eq!(handle.count(), 0);
+        assert_eq!(shared.snapshot().mean, 0.0);
+    }
+
+    #[test]
+    fn survives_a_panicking_reader() {
+        let shared: SharedMoving<u8> = SharedMoving::default();
+        let handle = shared.clone();
+        let _ = std::thread::spawn(move || handle.read(|_| panic!("reader failed"))).join();
+        shared.add(2);
+        assert_eq!(shared.mean(), 2.0);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{Moving, MovingInput};
+
+/// The statistics of a [`Moving`] at one point in time, as plain values
+/// that are cheap to copy across threads or format for reporting.
+///
+/// Unlike [`MovingState`](crate::MovingState), which holds what is needed
+/// to resume accumulating, a snapshot holds the derived results.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MovingSnapshot {
+    pub count: usize,
+    pub total_weight: f64,
+    pub mean: f64,
+    /// Sample variance.
+    pub variance: Option<f64>,
+    /// Sample standard deviation.
+    pub std_dev: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub first: Option<f64>,
+    pub last: Option<f64>,
+    /// Most frequent value, when mode tracking is enabled.
+    pub mode: Option<f64>,
+    /// Whether the memory budget forced mode tracking off exact counts, so
+    /// `mode` is the centre of a bin.
+    pub mode_degraded: bool,
+    /// Exact median, when enabled.
+    pub median: Option<f64>,
+    pub geometric_mean: Option<f64>,
+    pub harmonic_mean: Option<f64>,
+}
+
+impl<T> Moving<T>
+where
+    T: MovingInput,
+{
+    /// The current statistics, decoupled from the accumulator.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut latency: Moving<u32> = Moving::new().with_mode_tracking();
+    /// for millis in [10, 30, 10] {
+    ///     latency.add(millis);
+    /// }
+    /// let snapshot = latency.snapshot();
+    /// latency.reset();
+    ///
+    /// let reporter = std::thread::spawn(move || snapshot.mean);
+    /// assert_eq!(reporter.join().unwrap(), 50.0 / 3.0);
+    /// assert_eq!(snapshot.mode, Some(10.0));
+    /// assert_eq!(snapshot.max, Some(30.0));
+    /// ```
+    pub fn snapshot(&self) -> MovingSnapshot {
+        MovingSnapshot {
+            count: self.count(),
+            total_weight: self.total_weight(),
+            mean: self.mean(),
+            variance: self.variance(),
+            std_dev: self.std_dev(),
+            min: self.min(),
+            max: self.max(),
+            first: self.first(),
+            last: self.last(),
+            mode: self.mode(),
+            mode_degraded: self.is_mode_degraded(),
+            median: self.median(),
+            geometric_mean: self.geometric_mean(),
+            harmonic_mean: self.harmonic_mean(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_snapshot() {
+        let moving_average: Moving<f64> = Moving::new();
+        assert_eq!(moving_average.snapshot(), MovingSnapshot::default());
+    }
+
+    #[test]
+    fn snapshot_matches_accumulator() {
+        let mut moving_average: Moving<i64> = Moving::new().with_median();
+        for value in [4, -2, 7] {
+            moving_average.add(value);
+        }
+        let snapshot = moving_average.snapshot();
+        assert_eq!(snapshot.count, 3);
+        assert_eq!(snapshot.variance, Some(21.0));
+        assert_eq!(snapshot.median, Some(4.0));
+        assert_eq!((snapshot.first, snapshot.last), (Some(4.0), Some(7.0)));
+        assert_eq!(snapshot.mode, None);
+        assert_eq!(snapshot.geometric_mean, None);
+        assert!(!snapshot.mode_degraded);
+    }
+
+    #[test]
+    fn snapshot_reports_a_degraded_mode() {
+        let mut moving_average: Moving<u32> = Moving::new().with_memory_budget(64);
+        moving_average.add_iter(0..100);
+        assert!(moving_average.snapshot().mode_degraded);
+        moving_average.reset();
+        assert!(!moving_average.snapshot().mode_degraded);
+    }
+}
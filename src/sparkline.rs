@@ -0,0 +1,69 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::compat;
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Renders `values` as at most `width` bars, averaging consecutive values
+/// into buckets when there are more values than bars.
+pub(crate) fn sparkline(values: impl ExactSizeIterator<Item = f64>, width: usize) -> String {
+    let len = values.len();
+    if len == 0 || width == 0 {
+        return String::new();
+    }
+
+    let buckets = len.min(width);
+    let mut sums = vec![0.0; buckets];
+    let mut counts = vec![0usize; buckets];
+    for (i, value) in values.enumerate() {
+        let bucket = i * buckets / len;
+        sums[bucket] += value;
+        counts[bucket] += 1;
+    }
+    let means: Vec<f64> = sums
+        .iter()
+        .zip(&counts)
+        .map(|(sum, count)| sum / *count as f64)
+        .collect();
+
+    let min = means.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = means.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+    means
+        .iter()
+        .map(|mean| {
+            if range > 0.0 {
+                let level = compat::round((mean - min) / range * (BARS.len() - 1) as f64) as usize;
+                BARS[level.min(BARS.len() - 1)]
+            } else {
+                BARS[0]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_bar_per_value() {
+        let values = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        assert_eq!(sparkline(values.into_iter(), 10), "▁▂▃▄▅▆▇█");
+    }
+
+    #[test]
+    fn buckets_to_width() {
+        let values = [0.0, 0.0, 7.0, 7.0];
+        assert_eq!(sparkline(values.into_iter(), 2), "▁█");
+    }
+
+    #[test]
+    fn flat_and_empty() {
+        assert_eq!(sparkline([3.0, 3.0].into_iter(), 5), "▁▁");
+        assert_eq!(sparkline([].into_iter(), 5), "");
+        assert_eq!(sparkline([1.0].into_iter(), 0), "");
+    }
+}
@@ -0,0 +1,314 @@
+use alloc::vec::Vec;
+
+use crate::median::MedianTracker;
+use crate::mode::ModeTracker;
+use crate::{Moving, MovingInput, Statistic, Threshold};
+
+/// Plain-data snapshot of a [`Moving`], for persisting it across restarts
+/// or shipping it between services in whatever format the caller uses.
+///
+/// Configuration such as ingestion stages is not part of the snapshot; it
+/// is restored onto an accumulator configured as before with
+/// [`Moving::with_state`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MovingState {
+    pub count: usize,
+    /// Sum of the weights, see [`Moving::total_weight`].
+    pub total_weight: f64,
+    pub mean: f64,
+    /// Sum of squared deviations from the mean.
+    pub m2: f64,
+    /// Weighted sum of `log2` of the positive values.
+    pub log2_sum: f64,
+    /// Weighted sum of reciprocals of the positive values.
+    pub reciprocal_sum: f64,
+    /// Number of positive values; the geometric and harmonic means are only
+    /// known while every value was positive.
+    pub positive: usize,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub first: Option<f64>,
+    pub last: Option<f64>,
+    /// Value frequencies with the weight each value accumulated, when mode
+    /// tracking is enabled. Binned counts are listed at the centre of each
+    /// bin.
+    pub mode: Option<Vec<(f64, usize, f64)>>,
+    /// Every value and its multiplicity, when the median is tracked.
+    pub median: Option<Vec<(f64, usize)>>,
+    /// The configured threshold and the statistic it bounds.
+    pub threshold: Option<(Statistic, Threshold)>,
+    /// Whether the threshold is reached, or latched for a hysteresis
+    /// threshold, so a restore does not report it again.
+    pub threshold_reached: bool,
+}
+
+impl<T> Moving<T>
+where
+    T: MovingInput,
+{
+    /// Snapshot of the accumulated statistics.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut before: Moving<u32> = Moving::new().with_mode_tracking();
+    /// for value in [3, 5, 5] {
+    ///     before.add(value);
+    /// }
+    /// let state = before.state();
+    ///
+    /// let after: Moving<u32> = Moving::new().with_mode_tracking().with_state(state);
+    /// assert_eq!(after.count(), 3);
+    /// assert_eq!(after.mode(), Some(5.0));
+    /// ```
+    pub fn state(&self) -> MovingState {
+        MovingState {
+            count: self.count,
+            total_weight: self.weight,
+            mean: self.mean,
+            m2: self.m2,
+            log2_sum: self.log2_sum,
+            reciprocal_sum: self.reciprocal_sum,
+            positive: self.positive,
+            min: self.min,
+            max: self.max,
+            first: self.first,
+            last: self.last,
+            mode: self.mode.as_ref().map(ModeTracker::tallies),
+            median: self.median.as_ref().map(MedianTracker::counts),
+            threshold: self.threshold.clone(),
+            threshold_reached: self.threshold_reached,
+        }
+    }
+
+    /// Whether both accumulators hold the same statistics: count, mean,
+    /// variance, extremes and, where tracked, the same value frequencies.
+    /// Configuration is not compared, except for the threshold and whether
+    /// it is reached.
+    ///
+    /// ```rust
+    /// use moving_average::Moving;
+    ///
+    /// let mut baseline: Moving<u32> = Moving::new().with_mode_tracking();
+    /// baseline.add(4);
+    /// baseline.add(8);
+    /// let mut scenario = baseline.clone();
+    /// assert!(scenario.eq_state(&baseline));
+    ///
+    /// scenario.add(6);
+    /// assert_eq!(scenario, baseline.mean());
+    /// assert!(!scenario.eq_state(&baseline));
+    /// ```
+    pub fn eq_state(&self, other: &Moving<T>) -> bool {
+        let normalized = |moving: &Moving<T>| {
+            let mut state = moving.state();
+            if let Some(tallies) = &mut state.mode {
+                tallies.sort_by(|a, b| a.0.total_cmp(&b.0));
+            }
+            if let Some(counts) = &mut state.median {
+                counts.sort_by(|a, b| a.0.total_cmp(&b.0));
+            }
+            state
+        };
+        normalized(self) == normalized(other)
+    }
+
+    /// Starts from a count and mean persisted earlier, optionally with the
+    /// value frequencies behind the mode (as returned by
+    /// [`frequencies`](Self::frequencies)) and a threshold. Statistics not
+    /// given start from scratch, so the extremes are unknown and the
+    /// variance only reflects later values, and the geometric and harmonic
+    /// means stay `None`; use [`with_state`](Self::with_state) to restore
+    /// everything.
+    ///
+    /// ```rust
+    /// use moving_average::{Moving, Threshold};
+    ///
+    /// let mut resumed: Moving<u32> = Moving::from_parts(
+    ///     4,
+    ///     10.0,
+    ///     Some(vec![(5.0, 1), (10.0, 2), (15.0, 1)]),
+    ///     Some(Threshold::Upper(12.0)),
+    /// );
+    /// assert_eq!(resumed.mode(), Some(10.0));
+    /// assert!(resumed.add_with_result(40).is_err());
+    /// assert_eq!(resumed.mean(), 16.0);
+    /// ```
+    pub fn from_parts(
+        count: usize,
+        mean: f64,
+        mode: Option<Vec<(f64, usize)>>,
+        threshold: Option<Threshold>,
+    ) -> Self {
+        let moving = Self::new().with_state(MovingState {
+            count,
+            total_weight: count as f64,
+            mean: if count == 0 { 0.0 } else { mean },
+            mode: mode.map(|counts| {
+                counts
+                    .into_iter()
+                    .map(|(value, count)| (value, count, count as f64))
+                    .collect()
+            }),
+            ..MovingState::default()
+        });
+        match threshold {
+            Some(threshold) => moving.with_threshold(threshold),
+            None => moving,
+        }
+    }
+
+    /// Replaces the accumulated statistics with a snapshot taken by
+    /// [`state`](Self::state), keeping the configuration. Frequencies in the
+    /// snapshot enable mode or median tracking, and a threshold in it
+    /// replaces the configured one along with whether it was reached.
+    pub fn with_state(mut self, state: MovingState) -> Self {
+        self.clear_statistics();
+        self.count = state.count;
+        self.weight = state.total_weight;
+        self.mean = state.mean;
+        self.m2 = state.m2;
+        self.log2_sum = state.log2_sum;
+        self.reciprocal_sum = state.reciprocal_sum;
+        self.positive = state.positive;
+        self.min = state.min;
+        self.max = state.max;
+        self.first = state.first;
+        self.last = state.last;
+        if state.threshold.is_some() {
+            self.threshold = state.threshold;
+        }
+        self.threshold_reached = self.threshold.is_some() && state.threshold_reached;
+        if self.compensated.is_some() {
+            self = self.with_compensated_mean();
+        }
+        if let Some(tallies) = state.mode {
+            let mode = self.mode.get_or_insert_with(ModeTracker::default);
+            for (value, count, weight) in tallies {
+                mode.add_count(value, count, weight, state.mean);
+            }
+        }
+        if let Some(counts) = state.median {
+            let median = self.median.get_or_insert_with(MedianTracker::default);
+            for (value, count) in counts {
+                for _ in 0..count {
+                    median.add(value);
+                }
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut original: Moving<f64> = Moving::new().with_median();
+        for value in [2.5, -1.0, 8.0, 2.5] {
+            original.add(value);
+        }
+        let restored: Moving<f64> = Moving::new().with_state(original.state());
+        let (mut restored_state, mut original_state) = (restored.state(), original.state());
+        for state in [&mut restored_state, &mut original_state] {
+            state
+                .median
+                .as_mut()
+                .unwrap()
+                .sort_by(|a, b| a.0.total_cmp(&b.0));
+        }
+        assert_eq!(restored_state, original_state);
+        assert_eq!(restored.variance(), original.variance());
+        assert_eq!(restored.median(), Some(2.5));
+        assert_eq!(restored.mode(), None);
+        assert_eq!(restored.last(), Some(2.5));
+        assert_eq!(restored.geometric_mean(), None);
+    }
+
+    #[test]
+    fn from_parts_seeds_count_and_mean() {
+        let mut resumed: Moving<f64> = Moving::from_parts(3, 2.0, None, None);
+        assert_eq!(resumed.mode(), None);
+        assert_eq!(resumed.geometric_mean(), None);
+        resumed.add(6.0);
+        assert_eq!(resumed.count(), 4);
+        assert_eq!(resumed.mean(), 3.0);
+        assert_eq!(resumed.min(), Some(6.0));
+        let empty: Moving<f64> = Moving::from_parts(0, 9.0, None, None);
+        assert_eq!(empty.mean(), 0.0);
+    }
+
+    #[test]
+    fn with_state_keeps_the_geometric_and_harmonic_means() {
+        let mut original: Moving<f64> = Moving::new();
+        original.add(2.0);
+        original.add(8.0);
+        let mut restored: Moving<f64> = Moving::new().with_state(original.state());
+        assert_eq!(restored.geometric_mean(), Some(4.0));
+        restored.add(4.0);
+        assert!((restored.geometric_mean().unwrap() - 4.0).abs() < 1e-12);
+        assert!((restored.harmonic_mean().unwrap() - 24.0 / 7.0).abs() < 1e-12);
+
+        let mut initial: Moving<f64> = Moving::with_initial(5.0, 3);
+        initial.add(2.0);
+        assert_eq!(initial.geometric_mean(), None);
+        assert_eq!(initial.harmonic_mean(), None);
+    }
+
+    #[test]
+    fn restoring_does_not_notify_a_reset() {
+        use alloc::sync::Arc;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        let events = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::clone(&events);
+        let mut moving: Moving<f64> = Moving::new().with_observer(move |_| {
+            seen.fetch_add(1, Ordering::Relaxed);
+        });
+        moving.add(3.0);
+        let restored = moving.with_state(MovingState {
+            count: 2,
+            total_weight: 2.0,
+            mean: 5.0,
+            ..MovingState::default()
+        });
+        assert_eq!(events.load(Ordering::Relaxed), 0);
+        assert_eq!(restored.mean(), 5.0);
+    }
+
+    #[test]
+    fn latched_threshold_stays_latched() {
+        let mut original: Moving<f64> = Moving::new().with_threshold(Threshold::Hysteresis {
+            trigger: 10.0,
+            clear: 5.0,
+        });
+        assert!(original.add_with_result(12.0).is_err());
+        assert!(original.add_with_result(8.0).is_err());
+        let state = original.state();
+        assert!(state.threshold_reached);
+
+        let mut restored: Moving<f64> = Moving::new().with_state(state);
+        assert!(restored.eq_state(&original));
+        assert!(restored.is_threshold_reached());
+        // 29 / 3 is below the trigger but not past the clear level.
+        assert!(restored.add_with_result(9.0).is_err());
+        let outcome = restored.add_with_result(-30.0).unwrap();
+        assert!(outcome.threshold_cleared);
+        assert!(!restored.eq_state(&original));
+    }
+
+    #[test]
+    fn weighted_mode_round_trips() {
+        let mut original: Moving<u32> = Moving::new().with_mode_tracking();
+        original.add_slice(&[1, 1]);
+        original.add_weighted(7, 3.0).unwrap();
+        let mut restored: Moving<u32> = Moving::new().with_state(original.state());
+        assert!(restored.eq_state(&original));
+        assert_eq!(restored.mode(), Some(7.0));
+        // Tied on weight, 1 now wins on count.
+        restored.add(1);
+        assert_eq!(restored.mode(), Some(1.0));
+    }
+}
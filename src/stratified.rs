@@ -0,0 +1,132 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{Moving, MovingInput};
+
+/// Routes each sample into a value-range bucket, keeping a [`Moving`] per
+/// bucket alongside one for the whole stream.
+///
+/// `n` bounds define `n + 1` buckets: below the first bound, between each
+/// pair of consecutive bounds, and from the last bound upwards. Each bucket
+/// includes its lower bound.
+///
+/// ```rust
+/// use moving_average::Stratified;
+///
+/// let mut payloads: Stratified<usize> = Stratified::new([1_000.0, 100_000.0]);
+/// payloads.add(200);
+/// payloads.add(400);
+/// payloads.add(50_000);
+/// assert_eq!(*payloads.bucket(0).unwrap(), 300.0);
+/// assert_eq!(payloads.bucket(1).unwrap().count(), 1);
+/// assert_eq!(payloads.overall().count(), 3);
+/// ```
+#[derive(Debug)]
+pub struct Stratified<T> {
+    bounds: Vec<f64>,
+    buckets: Vec<Moving<T>>,
+    overall: Moving<T>,
+}
+
+impl<T> Stratified<T>
+where
+    T: MovingInput,
+{
+    pub fn new(bounds: impl Into<Vec<f64>>) -> Self {
+        let mut bounds = bounds.into();
+        bounds.sort_by(f64::total_cmp);
+        bounds.dedup();
+        let buckets = (0..=bounds.len()).map(|_| Moving::new()).collect();
+        Self {
+            bounds,
+            buckets,
+            overall: Moving::new(),
+        }
+    }
+
+    pub fn add(&mut self, value: T) {
+        let value = T::to_f64(value);
+        let index = self.bucket_index(value);
+        self.buckets[index].add_f64(value);
+        self.overall.add_f64(value);
+    }
+
+    /// Index of the bucket `value` falls into.
+    pub fn bucket_index(&self, value: f64) -> usize {
+        self.bounds.partition_point(|bound| *bound <= value)
+    }
+
+    pub fn bucket(&self, index: usize) -> Option<&Moving<T>> {
+        self.buckets.get(index)
+    }
+
+    /// Statistics of the bucket `value` falls into.
+    pub fn bucket_for(&self, value: f64) -> &Moving<T> {
+        &self.buckets[self.bucket_index(value)]
+    }
+
+    /// Value range covered by the bucket at `index`; the outer buckets are
+    /// unbounded.
+    pub fn bucket_range(&self, index: usize) -> Option<Range<f64>> {
+        if index > self.bounds.len() {
+            return None;
+        }
+        let start = index
+            .checked_sub(1)
+            .map_or(f64::NEG_INFINITY, |i| self.bounds[i]);
+        let end = self.bounds.get(index).copied().unwrap_or(f64::INFINITY);
+        Some(start..end)
+    }
+
+    /// Every bucket with its value range, in ascending order.
+    pub fn buckets(&self) -> impl Iterator<Item = (Range<f64>, &Moving<T>)> {
+        self.buckets
+            .iter()
+            .enumerate()
+            .map(|(index, bucket)| (self.bucket_range(index).unwrap(), bucket))
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// Statistics across every bucket.
+    pub fn overall(&self) -> &Moving<T> {
+        &self.overall
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_by_bound() {
+        let mut stratified: Stratified<f64> = Stratified::new([10.0, 0.0]);
+        for value in [-5.0, -1.0, 0.0, 9.0, 10.0, 30.0] {
+            stratified.add(value);
+        }
+        assert_eq!(stratified.len(), 3);
+        assert_eq!(*stratified.bucket(0).unwrap(), -3.0);
+        assert_eq!(*stratified.bucket(1).unwrap(), 4.5);
+        assert_eq!(*stratified.bucket(2).unwrap(), 20.0);
+        assert!((**stratified.overall() - 43.0 / 6.0).abs() < 1e-12);
+        assert_eq!(stratified.bucket_for(10.0).count(), 2);
+        assert!(stratified.bucket(3).is_none());
+    }
+
+    #[test]
+    fn bucket_ranges() {
+        let stratified: Stratified<u32> = Stratified::new(vec![1.0, 2.0]);
+        let ranges: Vec<Range<f64>> = stratified.buckets().map(|(range, _)| range).collect();
+        assert_eq!(
+            ranges,
+            vec![f64::NEG_INFINITY..1.0, 1.0..2.0, 2.0..f64::INFINITY]
+        );
+        assert_eq!(stratified.bucket_range(3), None);
+    }
+}
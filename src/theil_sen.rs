@@ -0,0 +1,151 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::sparkline::sparkline;
+use crate::window::Window;
+use crate::MovingInput;
+
+/// Robust trend estimate: the median of the pairwise slopes between the
+/// retained samples, indexed by arrival order.
+///
+/// Up to roughly 29% of the samples can be arbitrary outliers without
+/// pulling the slope away from the underlying trend.
+///
+/// ```rust
+/// use moving_average::TheilSen;
+///
+/// let mut theil_sen: TheilSen<f64> = TheilSen::new(10);
+/// for value in [1.0, 2.0, 3.0, 400.0, 5.0] {
+///     theil_sen.add(value);
+/// }
+/// assert_eq!(theil_sen.slope(), Some(1.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TheilSen<T> {
+    window: Window,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> TheilSen<T>
+where
+    T: MovingInput,
+{
+    /// Estimates over the last `window` samples; every estimate is
+    /// quadratic in the window size.
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: Window::new(window),
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    pub fn add(&mut self, value: T) {
+        self.window.push(T::to_f64(value));
+    }
+
+    /// Resizes the retained window, dropping the oldest samples when it
+    /// shrinks below the number currently held.
+    pub fn set_window(&mut self, window: usize) {
+        self.window.set_capacity(window);
+    }
+
+    pub fn window(&self) -> usize {
+        self.window.capacity()
+    }
+
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+
+    /// Unicode sparkline of the retained samples, at most `width` characters.
+    pub fn sparkline(&self, width: usize) -> String {
+        sparkline(self.window.iter(), width)
+    }
+
+    /// Change per sample; needs at least two samples.
+    pub fn slope(&self) -> Option<f64> {
+        let values: Vec<f64> = self.window.iter().collect();
+        let mut slopes = Vec::with_capacity(values.len() * values.len().saturating_sub(1) / 2);
+        for (i, a) in values.iter().enumerate() {
+            for (j, b) in values.iter().enumerate().skip(i + 1) {
+                slopes.push((b - a) / (j - i) as f64);
+            }
+        }
+        median(&mut slopes)
+    }
+
+    /// Value of the fitted line at the oldest retained sample.
+    pub fn intercept(&self) -> Option<f64> {
+        let slope = self.slope()?;
+        let mut intercepts: Vec<f64> = self
+            .window
+            .iter()
+            .enumerate()
+            .map(|(i, value)| value - slope * i as f64)
+            .collect();
+        median(&mut intercepts)
+    }
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        Some((values[middle - 1] + values[middle]) / 2.0)
+    } else {
+        Some(values[middle])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn needs_two_samples() {
+        let mut theil_sen: TheilSen<i32> = TheilSen::new(5);
+        assert_eq!(theil_sen.slope(), None);
+        theil_sen.add(3);
+        assert_eq!(theil_sen.slope(), None);
+        theil_sen.add(5);
+        assert_eq!(theil_sen.slope(), Some(2.0));
+        assert_eq!(theil_sen.intercept(), Some(3.0));
+    }
+
+    #[test]
+    fn ignores_outliers() {
+        let mut theil_sen: TheilSen<f64> = TheilSen::new(20);
+        for i in 0..20 {
+            let value = if i % 7 == 3 {
+                -1000.0
+            } else {
+                10.0 - 0.5 * i as f64
+            };
+            theil_sen.add(value);
+        }
+        assert_eq!(theil_sen.slope(), Some(-0.5));
+        assert_eq!(theil_sen.intercept(), Some(10.0));
+    }
+
+    #[test]
+    fn only_uses_retained_window() {
+        let mut theil_sen: TheilSen<usize> = TheilSen::new(3);
+        for value in [100, 0, 10, 20, 30] {
+            theil_sen.add(value);
+        }
+        assert_eq!(theil_sen.slope(), Some(10.0));
+        assert_eq!(theil_sen.intercept(), Some(10.0));
+        assert_eq!(theil_sen.sparkline(3), "▁▅█");
+
+        theil_sen.set_window(2);
+        assert_eq!(theil_sen.len(), 2);
+        assert_eq!(theil_sen.intercept(), Some(20.0));
+    }
+}
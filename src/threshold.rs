@@ -0,0 +1,151 @@
+use core::ops::Range;
+
+/// Statistic a [`Threshold`] is checked against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Statistic {
+    #[default]
+    Mean,
+    /// Number of values added, e.g. to stop after a sample budget.
+    Count,
+    /// Most frequent value; needs mode tracking.
+    Mode,
+    /// Sample variance; needs at least two values.
+    Variance,
+    /// The most recent value, to catch any single value past the bound.
+    Last,
+}
+
+/// Context of a [`MovingError::ThresholdReached`](crate::MovingError::ThresholdReached),
+/// enough to build an alert without querying the accumulator again.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThresholdBreach {
+    /// The statistic the threshold bounds.
+    pub statistic: Statistic,
+    /// Its value after the add or remove.
+    pub observed: f64,
+    /// The level it is past: the bound, the nearer end of a range, or the
+    /// trigger or clear level of a hysteresis threshold.
+    pub bound: f64,
+    /// The mean after the add or remove.
+    pub mean: f64,
+    /// The value that was added or removed.
+    pub value: f64,
+}
+
+/// Bound on a statistic, the mean unless configured otherwise; once the
+/// statistic is past it, adds report
+/// [`MovingError::ThresholdReached`](crate::MovingError::ThresholdReached).
+///
+/// A plain `f64` converts into an upper bound and a `Range<f64>` into a
+/// range the statistic has to stay within.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Threshold {
+    /// Reached at or above the bound, e.g. a latency ceiling.
+    Upper(f64),
+    /// Reached at or below the bound, e.g. a throughput floor.
+    Lower(f64),
+    /// Reached outside the range.
+    Range(Range<f64>),
+    /// Reached at `trigger` and latched until the statistic is back past
+    /// `clear`, so it does not flap around a single level. With `trigger`
+    /// above `clear` this bounds from above, otherwise from below.
+    Hysteresis { trigger: f64, clear: f64 },
+}
+
+impl Threshold {
+    /// Whether `value` alone reaches the threshold; a hysteresis threshold
+    /// is reached at its trigger level.
+    pub fn is_reached(&self, value: f64) -> bool {
+        match self {
+            Threshold::Upper(upper) => value >= *upper,
+            Threshold::Lower(lower) => value <= *lower,
+            Threshold::Range(range) => !range.contains(&value),
+            Threshold::Hysteresis { trigger, clear } if trigger >= clear => value >= *trigger,
+            Threshold::Hysteresis { trigger, .. } => value <= *trigger,
+        }
+    }
+
+    /// The level `value`, which reaches the threshold, is past.
+    pub(crate) fn bound(&self, value: f64) -> f64 {
+        match self {
+            Threshold::Upper(bound) | Threshold::Lower(bound) => *bound,
+            Threshold::Range(range) if value >= range.end => range.end,
+            Threshold::Range(range) => range.start,
+            Threshold::Hysteresis { trigger, .. } if self.is_reached(value) => *trigger,
+            Threshold::Hysteresis { clear, .. } => *clear,
+        }
+    }
+
+    /// Whether the threshold is reached given whether it was before.
+    pub(crate) fn update(&self, value: f64, latched: bool) -> bool {
+        match self {
+            Threshold::Hysteresis { trigger, clear } if latched => {
+                if trigger >= clear {
+                    value > *clear
+                } else {
+                    value < *clear
+                }
+            }
+            _ => self.is_reached(value),
+        }
+    }
+}
+
+impl From<f64> for Threshold {
+    fn from(upper: f64) -> Self {
+        Threshold::Upper(upper)
+    }
+}
+
+impl From<Range<f64>> for Threshold {
+    fn from(range: Range<f64>) -> Self {
+        Threshold::Range(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        assert!(Threshold::from(10.0).is_reached(10.0));
+        assert!(!Threshold::Upper(10.0).is_reached(9.9));
+        assert!(Threshold::Lower(1.0).is_reached(0.5));
+        assert!(!Threshold::Lower(1.0).is_reached(1.5));
+        let range = Threshold::from(1.0..2.0);
+        assert!(!range.is_reached(1.0));
+        assert!(range.is_reached(2.0));
+        assert!(range.is_reached(0.0));
+    }
+
+    #[test]
+    fn hysteresis_latches() {
+        let upper = Threshold::Hysteresis {
+            trigger: 10.0,
+            clear: 8.0,
+        };
+        assert!(!upper.update(9.0, false));
+        assert!(upper.update(10.0, false));
+        assert!(upper.update(9.0, true));
+        assert!(!upper.update(8.0, true));
+
+        let lower = Threshold::Hysteresis {
+            trigger: 2.0,
+            clear: 5.0,
+        };
+        assert!(lower.update(1.0, false));
+        assert!(lower.update(4.0, true));
+        assert!(!lower.update(5.0, true));
+        assert!(!lower.is_reached(3.0));
+        assert_eq!(upper.bound(9.0), 8.0);
+        assert_eq!(upper.bound(12.0), 10.0);
+    }
+
+    #[test]
+    fn range_bound_is_the_nearer_end() {
+        let range = Threshold::from(1.0..2.0);
+        assert_eq!(range.bound(0.5), 1.0);
+        assert_eq!(range.bound(3.0), 2.0);
+    }
+}
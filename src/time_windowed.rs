@@ -0,0 +1,241 @@
+use alloc::collections::VecDeque;
+use core::ops::Deref;
+use std::time::{Duration, Instant};
+
+use crate::MovingInput;
+
+/// Average over the samples observed within the last `span` of time.
+///
+/// Samples are evicted once they are older than `span` relative to the
+/// newest timestamp seen, or to the instant passed to
+/// [`expire`](Self::expire) when no new samples arrive.
+///
+/// ```rust
+/// use moving_average::TimeWindowedMoving;
+/// use std::time::{Duration, Instant};
+///
+/// let start = Instant::now();
+/// let mut windowed: TimeWindowedMoving<u32> = TimeWindowedMoving::new(Duration::from_secs(60));
+/// windowed.add_at(100, start);
+/// windowed.add_at(10, start + Duration::from_secs(30));
+/// windowed.add_at(20, start + Duration::from_secs(90));
+/// assert_eq!(*windowed, 15.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimeWindowedMoving<T> {
+    span: Duration,
+    samples: VecDeque<(Instant, f64)>,
+    sum: f64,
+    mean: f64,
+    evictions: usize,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> TimeWindowedMoving<T>
+where
+    T: MovingInput,
+{
+    pub fn new(span: Duration) -> Self {
+        Self {
+            span,
+            samples: VecDeque::new(),
+            sum: 0.0,
+            mean: 0.0,
+            evictions: 0,
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Adds a value observed now.
+    pub fn add(&mut self, value: T) {
+        self.add_at(value, Instant::now());
+    }
+
+    /// Adds a value observed at `at`; samples may arrive out of order.
+    pub fn add_at(&mut self, value: T, at: Instant) {
+        let value = T::to_f64(value);
+        let position = self.samples.partition_point(|(time, _)| *time <= at);
+        self.samples.insert(position, (at, value));
+        self.sum += value;
+        let newest = self.samples.back().map_or(at, |(time, _)| *time);
+        self.expire(newest);
+    }
+
+    /// Evicts samples older than `span` before `now`.
+    pub fn expire(&mut self, now: Instant) {
+        let Some(cutoff) = now.checked_sub(self.span) else {
+            self.update_mean();
+            return;
+        };
+        while self.samples.front().is_some_and(|(time, _)| *time < cutoff) {
+            if let Some((_, value)) = self.samples.pop_front() {
+                self.sum -= value;
+                self.evictions += 1;
+            }
+        }
+        // Re-add from scratch once the whole window has turned over so
+        // rounding error from the running subtraction cannot build up.
+        if self.evictions >= self.samples.len() {
+            self.evictions = 0;
+            self.sum = self.samples.iter().map(|(_, value)| value).sum();
+        }
+        self.update_mean();
+    }
+
+    fn update_mean(&mut self) {
+        self.mean = if self.samples.is_empty() {
+            0.0
+        } else {
+            self.sum / self.samples.len() as f64
+        };
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Number of samples currently in the window.
+    pub fn count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Smallest sample in the window.
+    pub fn min(&self) -> Option<f64> {
+        self.samples
+            .iter()
+            .map(|(_, value)| *value)
+            .reduce(f64::min)
+    }
+
+    /// Largest sample in the window.
+    pub fn max(&self) -> Option<f64> {
+        self.samples
+            .iter()
+            .map(|(_, value)| *value)
+            .reduce(f64::max)
+    }
+
+    /// Samples per second over the window: the number of samples in it
+    /// divided by the span. Call [`expire`](Self::expire) first so a quiet
+    /// period counts as one.
+    ///
+    /// ```rust
+    /// use moving_average::TimeWindowedMoving;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let start = Instant::now();
+    /// let mut requests: TimeWindowedMoving<u32> = TimeWindowedMoving::new(Duration::from_secs(10));
+    /// for second in 0..30 {
+    ///     requests.add_at(200, start + Duration::from_millis(second * 500));
+    /// }
+    /// assert_eq!(requests.rate(), 2.1);
+    /// requests.expire(start + Duration::from_secs(20));
+    /// assert_eq!(requests.rate(), 1.0);
+    /// ```
+    pub fn rate(&self) -> f64 {
+        let seconds = self.span.as_secs_f64();
+        if seconds > 0.0 {
+            self.samples.len() as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+
+    pub fn span(&self) -> Duration {
+        self.span
+    }
+
+    /// Changes the span, evicting the samples a shorter span no longer
+    /// covers relative to the newest one.
+    pub fn set_span(&mut self, span: Duration) {
+        self.span = span;
+        if let Some((newest, _)) = self.samples.back() {
+            self.expire(*newest);
+        }
+    }
+}
+
+impl<T> Deref for TimeWindowedMoving<T> {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.mean
+    }
+}
+
+impl<T> core::fmt::Display for TimeWindowedMoving<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.mean)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_by_age() {
+        let start = Instant::now();
+        let mut windowed: TimeWindowedMoving<f64> =
+            TimeWindowedMoving::new(Duration::from_secs(10));
+        windowed.add_at(1.0, start);
+        windowed.add_at(2.0, start + Duration::from_secs(5));
+        windowed.add_at(3.0, start + Duration::from_secs(10));
+        assert_eq!(windowed.count(), 3);
+        assert_eq!(windowed.mean(), 2.0);
+        windowed.add_at(4.0, start + Duration::from_secs(11));
+        assert_eq!(windowed.count(), 3);
+        assert_eq!(windowed.mean(), 3.0);
+        assert_eq!(windowed.min(), Some(2.0));
+        assert_eq!(windowed.max(), Some(4.0));
+    }
+
+    #[test]
+    fn expire_without_new_samples() {
+        let start = Instant::now();
+        let mut windowed: TimeWindowedMoving<u32> = TimeWindowedMoving::new(Duration::from_secs(1));
+        windowed.add_at(7, start);
+        windowed.expire(start + Duration::from_secs(5));
+        assert_eq!(windowed.count(), 0);
+        assert_eq!(windowed.mean(), 0.0);
+    }
+
+    #[test]
+    fn out_of_order_samples() {
+        let start = Instant::now();
+        let mut windowed: TimeWindowedMoving<u32> =
+            TimeWindowedMoving::new(Duration::from_secs(10));
+        windowed.add_at(5, start + Duration::from_secs(20));
+        windowed.add_at(100, start);
+        windowed.add_at(7, start + Duration::from_secs(15));
+        assert_eq!(windowed.count(), 2);
+        assert_eq!(windowed.mean(), 6.0);
+    }
+
+    #[test]
+    fn add_uses_the_current_time() {
+        let mut windowed: TimeWindowedMoving<u32> =
+            TimeWindowedMoving::new(Duration::from_secs(60));
+        windowed.add(2);
+        windowed.add(4);
+        assert_eq!(windowed.mean(), 3.0);
+    }
+
+    #[test]
+    fn set_span_evicts_on_shrink() {
+        let start = Instant::now();
+        let mut windowed: TimeWindowedMoving<u32> =
+            TimeWindowedMoving::new(Duration::from_secs(60));
+        for (second, value) in [(0, 10), (20, 20), (40, 30)] {
+            windowed.add_at(value, start + Duration::from_secs(second));
+        }
+        windowed.set_span(Duration::from_secs(120));
+        assert_eq!(windowed.count(), 3);
+        windowed.set_span(Duration::from_secs(25));
+        assert_eq!(windowed.span(), Duration::from_secs(25));
+        assert_eq!(windowed.count(), 2);
+        assert_eq!(windowed.mean(), 25.0);
+        windowed.add_at(50, start + Duration::from_secs(60));
+        assert_eq!(windowed.mean(), 40.0);
+    }
+}
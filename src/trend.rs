@@ -0,0 +1,116 @@
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use crate::{MovingInput, MovingPair};
+
+/// Least-squares line through the values against their position, or
+/// against time when added with [`add_at`](Self::add_at), to tell whether a
+/// metric is trending before its mean crosses a threshold.
+///
+/// ```rust
+/// use moving_average::Trend;
+///
+/// let mut disk: Trend<f64> = Trend::new();
+/// for used in [50.0, 53.0, 53.0, 55.0, 59.0] {
+///     disk.add(used);
+/// }
+/// assert_eq!(disk.slope(), Some(2.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Trend<T> {
+    pair: MovingPair<f64>,
+    #[cfg(feature = "std")]
+    origin: Option<Instant>,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> Default for Trend<T> {
+    fn default() -> Self {
+        Self {
+            pair: MovingPair::default(),
+            #[cfg(feature = "std")]
+            origin: None,
+            phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<T> Trend<T>
+where
+    T: MovingInput,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the next value, at the position after the previous one.
+    pub fn add(&mut self, value: T) {
+        let index = self.pair.count() as f64;
+        self.pair.add_f64(index, value.to_f64());
+    }
+
+    /// Adds a value observed at `at`; the line is then over seconds since
+    /// the first timestamp. Don't mix with [`add`](Self::add).
+    #[cfg(feature = "std")]
+    pub fn add_at(&mut self, value: T, at: Instant) {
+        let origin = *self.origin.get_or_insert(at);
+        let seconds = at.saturating_duration_since(origin).as_secs_f64();
+        self.pair.add_f64(seconds, value.to_f64());
+    }
+
+    pub fn count(&self) -> usize {
+        self.pair.count()
+    }
+
+    /// Change per position, or per second for timestamped values; `None`
+    /// until there are two distinct positions.
+    pub fn slope(&self) -> Option<f64> {
+        self.pair.slope()
+    }
+
+    /// Fitted value at the first position, or at the first timestamp.
+    pub fn intercept(&self) -> Option<f64> {
+        self.pair.intercept()
+    }
+
+    /// Fitted value `steps` positions, or seconds, after the first.
+    pub fn predict(&self, steps: f64) -> Option<f64> {
+        Some(self.intercept()? + self.slope()? * steps)
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fits_positions() {
+        let mut trend: Trend<i32> = Trend::new();
+        trend.add(7);
+        assert_eq!(trend.slope(), None);
+        for value in [5, 3, 1] {
+            trend.add(value);
+        }
+        assert_eq!(trend.slope(), Some(-2.0));
+        assert_eq!(trend.intercept(), Some(7.0));
+        assert_eq!(trend.predict(10.0), Some(-13.0));
+        trend.reset();
+        assert_eq!(trend.count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn fits_timestamps() {
+        let start = Instant::now();
+        let mut trend: Trend<f64> = Trend::new();
+        for (seconds, value) in [(0, 1.0), (10, 6.0), (30, 16.0)] {
+            trend.add_at(value, start + std::time::Duration::from_secs(seconds));
+        }
+        assert!((trend.slope().unwrap() - 0.5).abs() < 1e-12);
+        assert!((trend.intercept().unwrap() - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,121 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{compat, MovingError, MovingInput};
+
+/// Element-wise average of fixed-length vectors, e.g. positions or
+/// embeddings, with one mean and variance per dimension.
+///
+/// ```rust
+/// use moving_average::MovingVec;
+///
+/// let mut position: MovingVec<f64> = MovingVec::new(2);
+/// position.add(&[1.0, 2.0]).unwrap();
+/// position.add(&[5.0, 4.0]).unwrap();
+/// assert_eq!(position.mean(), [3.0, 3.0]);
+/// assert_eq!(position.magnitude(), 18f64.sqrt());
+/// assert!(position.add(&[1.0]).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MovingVec<T> {
+    count: usize,
+    mean: Vec<f64>,
+    m2: Vec<f64>,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> MovingVec<T>
+where
+    T: MovingInput + Copy,
+{
+    /// Averages vectors of `dimensions` elements.
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            count: 0,
+            mean: vec![0.0; dimensions],
+            m2: vec![0.0; dimensions],
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Adds a vector, e.g. a slice, array or `Vec`, with exactly
+    /// [`dimensions`](Self::dimensions) elements.
+    pub fn add(&mut self, value: impl AsRef<[T]>) -> Result<(), MovingError> {
+        let value = value.as_ref();
+        if value.len() != self.mean.len() {
+            return Err(MovingError::DimensionMismatch {
+                expected: self.mean.len(),
+                actual: value.len(),
+            });
+        }
+        self.count += 1;
+        let n = self.count as f64;
+        for ((element, mean), m2) in value.iter().zip(&mut self.mean).zip(&mut self.m2) {
+            let element = element.to_f64();
+            let delta = element - *mean;
+            *mean += delta / n;
+            *m2 += delta * (element - *mean);
+        }
+        Ok(())
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.mean.len()
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The mean of each dimension; all zero before any vector was added.
+    pub fn mean(&self) -> &[f64] {
+        &self.mean
+    }
+
+    pub fn mean_of(&self, dimension: usize) -> Option<f64> {
+        self.mean.get(dimension).copied()
+    }
+
+    /// Sample variance of each dimension; needs at least two vectors.
+    pub fn variance(&self) -> Option<Vec<f64>> {
+        let n = (self.count > 1).then(|| (self.count - 1) as f64)?;
+        Some(self.m2.iter().map(|m2| m2 / n).collect())
+    }
+
+    /// Euclidean length of the mean vector.
+    pub fn magnitude(&self) -> f64 {
+        compat::sqrt(self.mean.iter().map(|mean| mean * mean).sum())
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.dimensions());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages_each_dimension() {
+        let mut vectors: MovingVec<i32> = MovingVec::new(3);
+        assert_eq!(vectors.variance(), None);
+        vectors.add([1, -2, 0]).unwrap();
+        vectors.add(vec![3, -4, 0]).unwrap();
+        assert_eq!(vectors.mean(), [2.0, -3.0, 0.0]);
+        assert_eq!(vectors.mean_of(1), Some(-3.0));
+        assert_eq!(vectors.mean_of(3), None);
+        assert_eq!(vectors.variance(), Some(vec![2.0, 2.0, 0.0]));
+        assert_eq!(
+            vectors.add([1, 2]),
+            Err(MovingError::DimensionMismatch {
+                expected: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(vectors.count(), 2);
+        vectors.reset();
+        assert_eq!((vectors.count(), vectors.dimensions()), (0, 3));
+        assert_eq!(vectors.magnitude(), 0.0);
+    }
+}
@@ -0,0 +1,136 @@
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use crate::window::Window;
+use crate::MovingInput;
+
+/// Weighted moving average (WMA) over the last `n` samples, where each
+/// position in the window carries its own weight.
+///
+/// Weights are listed from the oldest position to the newest. Until the
+/// window is full, the samples held take the weights of the newest
+/// positions.
+///
+/// ```rust
+/// use moving_average::WeightedWindow;
+///
+/// let mut wma: WeightedWindow<u32> = WeightedWindow::linear(3);
+/// for price in [10, 20, 30, 40] {
+///     wma.add(price);
+/// }
+/// // (20 * 1 + 30 * 2 + 40 * 3) / 6
+/// assert_eq!(wma.mean(), 200.0 / 6.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WeightedWindow<T> {
+    weights: Vec<f64>,
+    window: Window,
+    mean: f64,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> WeightedWindow<T>
+where
+    T: MovingInput,
+{
+    /// The window holds one sample per weight.
+    pub fn new(weights: impl Into<Vec<f64>>) -> Self {
+        let weights = weights.into();
+        Self {
+            window: Window::new(weights.len()),
+            weights,
+            mean: 0.0,
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Weights `1, 2, ..., n`, the newest sample weighing most.
+    pub fn linear(n: usize) -> Self {
+        Self::new((1..=n).map(|weight| weight as f64).collect::<Vec<_>>())
+    }
+
+    /// Weights rising to the middle of the window and falling again, e.g.
+    /// `1, 2, 3, 2, 1`.
+    pub fn triangular(n: usize) -> Self {
+        Self::new(
+            (0..n)
+                .map(|position| (position + 1).min(n - position) as f64)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    pub fn add(&mut self, value: T) {
+        self.window.push(T::to_f64(value));
+        let weights = &self.weights[self.weights.len() - self.window.len()..];
+        let (sum, total) = self
+            .window
+            .iter()
+            .zip(weights)
+            .fold((0.0, 0.0), |(sum, total), (value, weight)| {
+                (sum + value * weight, total + weight)
+            });
+        self.mean = if total == 0.0 { 0.0 } else { sum / total };
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Number of samples currently in the window.
+    pub fn count(&self) -> usize {
+        self.window.len()
+    }
+
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.window.len() == self.window.capacity()
+    }
+}
+
+impl<T> Deref for WeightedWindow<T> {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.mean
+    }
+}
+
+impl<T> core::fmt::Display for WeightedWindow<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.mean)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_window_uses_newest_weights() {
+        let mut wma: WeightedWindow<f64> = WeightedWindow::new([1.0, 1.0, 3.0]);
+        wma.add(4.0);
+        assert_eq!(wma.mean(), 4.0);
+        wma.add(8.0);
+        assert_eq!(wma.mean(), 7.0);
+        assert!(!wma.is_full());
+    }
+
+    #[test]
+    fn triangular_weights() {
+        let wma: WeightedWindow<u8> = WeightedWindow::triangular(5);
+        assert_eq!(wma.weights(), &[1.0, 2.0, 3.0, 2.0, 1.0]);
+        let wma: WeightedWindow<u8> = WeightedWindow::triangular(4);
+        assert_eq!(wma.weights(), &[1.0, 2.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn empty_window_stays_zero() {
+        let mut wma: WeightedWindow<i32> = WeightedWindow::new(Vec::new());
+        wma.add(5);
+        assert_eq!(wma.mean(), 0.0);
+        assert_eq!(wma.count(), 0);
+    }
+}
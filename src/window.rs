@@ -0,0 +1,95 @@
+use alloc::collections::VecDeque;
+
+/// Bounded buffer of the most recent samples, shared by the windowed types.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Window {
+    values: VecDeque<f64>,
+    capacity: usize,
+}
+
+impl Window {
+    /// The buffer grows as values arrive rather than reserving the whole
+    /// capacity, so a very large window costs only what it holds.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            values: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Pushes a value, returning the evicted one once the window is full.
+    pub(crate) fn push(&mut self, value: f64) -> Option<f64> {
+        if self.capacity == 0 {
+            return Some(value);
+        }
+        let evicted = if self.values.len() == self.capacity {
+            self.values.pop_front()
+        } else {
+            None
+        };
+        self.values.push_back(value);
+        evicted
+    }
+
+    /// Changes the capacity, evicting the oldest values that no longer fit.
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        while self.values.len() > capacity {
+            self.values.pop_front();
+        }
+        self.capacity = capacity;
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<f64> {
+        self.values.get(index).copied()
+    }
+
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = f64> + ExactSizeIterator + '_ {
+        self.values.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_oldest() {
+        let mut window = Window::new(2);
+        assert_eq!(window.push(1.0), None);
+        assert_eq!(window.push(2.0), None);
+        assert_eq!(window.push(3.0), Some(1.0));
+        assert_eq!(window.iter().collect::<Vec<_>>(), vec![2.0, 3.0]);
+    }
+
+    #[test]
+    fn resizing() {
+        let mut window = Window::new(3);
+        for value in [1.0, 2.0, 3.0] {
+            window.push(value);
+        }
+        window.set_capacity(1);
+        assert_eq!(window.iter().collect::<Vec<_>>(), vec![3.0]);
+        window.set_capacity(2);
+        assert_eq!(window.push(4.0), None);
+        assert_eq!(window.push(5.0), Some(3.0));
+    }
+
+    #[test]
+    fn huge_capacity_is_not_reserved() {
+        let mut window = Window::new(usize::MAX);
+        assert_eq!(window.push(1.0), None);
+        assert_eq!(window.capacity(), usize::MAX);
+    }
+}
@@ -0,0 +1,190 @@
+use alloc::string::String;
+use core::ops::Deref;
+
+use crate::median::trimmed_mean;
+use crate::sparkline::sparkline;
+use crate::window::Window;
+use crate::MovingInput;
+
+/// Average over only the last `n` samples, evicting the oldest as new ones
+/// arrive.
+///
+/// ```rust
+/// use moving_average::WindowedMoving;
+///
+/// let mut windowed: WindowedMoving<u32> = WindowedMoving::new(2);
+/// windowed.add(10);
+/// windowed.add(20);
+/// windowed.add(40);
+/// assert_eq!(*windowed, 30.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WindowedMoving<T> {
+    window: Window,
+    sum: f64,
+    mean: f64,
+    evictions: usize,
+    phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> WindowedMoving<T>
+where
+    T: MovingInput,
+{
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: Window::new(window),
+            sum: 0.0,
+            mean: 0.0,
+            evictions: 0,
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    pub fn add(&mut self, value: T) {
+        let value = T::to_f64(value);
+        self.sum += value;
+        if let Some(evicted) = self.window.push(value) {
+            self.sum -= evicted;
+            self.evictions += 1;
+            // Re-add from scratch once per window turnover so rounding
+            // error from the running subtraction cannot build up.
+            if self.evictions >= self.window.capacity() {
+                self.evictions = 0;
+                self.sum = self.window.iter().sum();
+            }
+        }
+        self.update_mean();
+    }
+
+    fn update_mean(&mut self) {
+        self.mean = if self.window.is_empty() {
+            0.0
+        } else {
+            self.sum / self.window.len() as f64
+        };
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Number of samples currently in the window.
+    pub fn count(&self) -> usize {
+        self.window.len()
+    }
+
+    pub fn window(&self) -> usize {
+        self.window.capacity()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.window.len() == self.window.capacity()
+    }
+
+    /// Resizes the window, dropping the oldest samples when it shrinks
+    /// below the number currently held.
+    pub fn set_window(&mut self, window: usize) {
+        self.window.set_capacity(window);
+        self.sum = self.window.iter().sum();
+        self.evictions = 0;
+        self.update_mean();
+    }
+
+    /// Smallest sample in the window.
+    pub fn min(&self) -> Option<f64> {
+        self.window.iter().reduce(f64::min)
+    }
+
+    /// Largest sample in the window.
+    pub fn max(&self) -> Option<f64> {
+        self.window.iter().reduce(f64::max)
+    }
+
+    /// Mean of the window after dropping `fraction` of its samples from
+    /// each end, so a few garbage readings do not skew it; see
+    /// [`Moving::trimmed_mean`](crate::Moving::trimmed_mean).
+    ///
+    /// ```rust
+    /// use moving_average::WindowedMoving;
+    ///
+    /// let mut windowed: WindowedMoving<u32> = WindowedMoving::new(10);
+    /// for value in [10, 11, 9, 10, 9999, 10, 11, 9, 0, 10] {
+    ///     windowed.add(value);
+    /// }
+    /// assert_eq!(windowed.trimmed_mean(0.1), Some(10.0));
+    /// ```
+    pub fn trimmed_mean(&self, fraction: f64) -> Option<f64> {
+        trimmed_mean(
+            self.window.iter().map(|value| (value, 1)).collect(),
+            fraction,
+        )
+    }
+
+    /// Unicode sparkline of the samples in the window, at most `width`
+    /// characters.
+    pub fn sparkline(&self, width: usize) -> String {
+        sparkline(self.window.iter(), width)
+    }
+}
+
+impl<T> Deref for WindowedMoving<T> {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.mean
+    }
+}
+
+impl<T> core::fmt::Display for WindowedMoving<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.mean)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages_last_n() {
+        let mut windowed: WindowedMoving<i32> = WindowedMoving::new(3);
+        assert_eq!(windowed.mean(), 0.0);
+        for value in [1, 2, 3] {
+            windowed.add(value);
+        }
+        assert!(windowed.is_full());
+        assert_eq!(windowed.mean(), 2.0);
+        windowed.add(10);
+        assert_eq!(windowed.count(), 3);
+        assert_eq!(windowed.mean(), 5.0);
+        assert_eq!(windowed.min(), Some(2.0));
+        assert_eq!(windowed.max(), Some(10.0));
+    }
+
+    #[test]
+    fn no_drift_over_many_evictions() {
+        let mut windowed: WindowedMoving<f64> = WindowedMoving::new(4);
+        for i in 0..100_000 {
+            windowed.add(if i % 2 == 0 { 1e12 } else { 0.1 });
+        }
+        for _ in 0..4 {
+            windowed.add(0.1);
+        }
+        assert_eq!(windowed.mean(), 0.1);
+    }
+
+    #[test]
+    fn resizing() {
+        let mut windowed: WindowedMoving<u8> = WindowedMoving::new(4);
+        for value in [1, 2, 3, 4] {
+            windowed.add(value);
+        }
+        windowed.set_window(2);
+        assert_eq!(windowed.mean(), 3.5);
+        windowed.set_window(3);
+        windowed.add(6);
+        assert_eq!(windowed.mean(), 13.0 / 3.0);
+        assert_eq!(windowed.sparkline(3), "▁▃█");
+    }
+}